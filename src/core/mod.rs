@@ -1,7 +1,9 @@
 mod camera;
+mod onb;
 mod ray;
 mod vector;
 
 pub use camera::Camera;
+pub use onb::Onb;
 pub use ray::Ray;
 pub use vector::{Color, Point, Vector};
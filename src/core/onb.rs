@@ -0,0 +1,58 @@
+use super::Vector;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Onb {
+    u: Vector,
+    v: Vector,
+    w: Vector,
+}
+
+impl Onb {
+    pub fn from_w(n: Vector) -> Self {
+        let w = n.unit_vector();
+        let a = if w.x().abs() > 0.9 {
+            Vector::new(0, 1, 0)
+        } else {
+            Vector::new(1, 0, 0)
+        };
+        let v = w.cross(a).unit_vector();
+        let u = w.cross(v);
+
+        Self { u, v, w }
+    }
+
+    // Frame around `n` whose u axis follows the tangent `u`, so that anisotropic
+    // materials stay aligned with the surface parameterization. Falls back to
+    // `from_w` where the tangent is degenerate or parallel to `n`.
+    pub fn from_wu(n: Vector, u: Vector) -> Self {
+        let w = n.unit_vector();
+        let tangent = u - w * w.dot(u);
+        if tangent.length_squared() <= 1e-12 * u.length_squared() {
+            return Self::from_w(n);
+        }
+
+        let u = tangent.unit_vector();
+        let v = w.cross(u);
+        Self { u, v, w }
+    }
+
+    pub fn u(&self) -> Vector {
+        self.u
+    }
+
+    pub fn v(&self) -> Vector {
+        self.v
+    }
+
+    pub fn w(&self) -> Vector {
+        self.w
+    }
+
+    pub fn local(&self, a: Vector) -> Vector {
+        self.u * a.x() + self.v * a.y() + self.w * a.z()
+    }
+
+    pub fn to_local(&self, a: Vector) -> Vector {
+        Vector::new(a.dot(self.u), a.dot(self.v), a.dot(self.w))
+    }
+}
@@ -49,6 +49,10 @@ impl Vector {
         self.x * self.x + self.y * self.y + self.z * self.z
     }
 
+    pub fn max_component(&self) -> f64 {
+        self.x.max(self.y).max(self.z)
+    }
+
     pub fn dot(&self, v: Vector) -> f64 {
         self.x * v.x + self.y * v.y + self.z * v.z
     }
@@ -109,6 +113,15 @@ impl Vector {
         }
     }
 
+    pub fn random_cosine_direction(rng: &mut ThreadRng) -> Self {
+        let r1 = rng.gen::<f64>();
+        let r2 = rng.gen::<f64>();
+        let phi = 2.0 * PI * r1;
+        let z = (1.0 - r2).sqrt();
+        let r = r2.sqrt();
+        Self::new(phi.cos() * r, phi.sin() * r, z)
+    }
+
     pub fn random_in_unit_disk(rng: &mut ThreadRng) -> Self {
         loop {
             let p = Self::new(rng.gen::<f64>(), rng.gen::<f64>(), 0.0) * 2.0 - Self::new(1, 1, 0);
@@ -229,6 +242,13 @@ impl ops::Mul<f64> for &Vector {
     }
 }
 
+impl ops::Div for Vector {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::new(self.x / rhs.x, self.y / rhs.y, self.z / rhs.z)
+    }
+}
+
 impl ops::DivAssign<f64> for Vector {
     fn div_assign(&mut self, rhs: f64) {
         *self *= 1.0 / rhs;
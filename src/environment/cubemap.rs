@@ -0,0 +1,157 @@
+use super::Environment;
+use crate::core::{Color, Vector};
+
+use image::error::{ParameterError, ParameterErrorKind};
+use image::{self, ImageError, Rgb};
+
+// Six square images on the faces of a cube around the scene, laid out as in
+// OpenGL: +x, -x, +y, -y, +z and -z. Faces are ordinary 8-bit sRGB images and
+// are decoded to linear radiance when loaded.
+pub struct Cubemap {
+    // Pixels of each face row by row from the top, all `size` by `size`.
+    faces: Vec<Vec<Color>>,
+    size: usize,
+}
+
+impl Cubemap {
+    pub fn new(filenames: [&str; 6]) -> Result<Self, ImageError> {
+        let mut faces = Vec::with_capacity(6);
+        let mut size = None;
+
+        for filename in filenames.iter() {
+            let face = image::open(filename)?.to_rgb();
+            let (width, height) = face.dimensions();
+            if width != height || size.is_some_and(|size| size != width) {
+                return Err(ImageError::Parameter(ParameterError::from_kind(
+                    ParameterErrorKind::DimensionMismatch,
+                )));
+            }
+            size = Some(width);
+
+            faces.push(
+                face.pixels()
+                    .map(|&Rgb([r, g, b])| {
+                        Color::new(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b))
+                    })
+                    .collect(),
+            );
+        }
+
+        Ok(Self {
+            faces,
+            size: size.unwrap_or(0) as usize,
+        })
+    }
+}
+
+impl Environment for Cubemap {
+    fn value(&self, direction: Vector) -> Color {
+        let (x, y, z) = (direction.x(), direction.y(), direction.z());
+        let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+
+        // Face, the coordinates across it with t pointing down the image, and
+        // the length of the major axis.
+        let (face, s, t, major) = if ax >= ay && ax >= az {
+            if x > 0.0 {
+                (0, -z, -y, ax)
+            } else {
+                (1, z, -y, ax)
+            }
+        } else if ay >= az {
+            if y > 0.0 {
+                (2, x, z, ay)
+            } else {
+                (3, x, -z, ay)
+            }
+        } else if z > 0.0 {
+            (4, x, -y, az)
+        } else {
+            (5, -x, -y, az)
+        };
+
+        let pixel = |coordinate: f64| {
+            let scaled = 0.5 * (coordinate / major + 1.0) * self.size as f64;
+            (scaled.max(0.0) as usize).min(self.size - 1)
+        };
+        self.faces[face][pixel(t) * self.size + pixel(s)]
+    }
+}
+
+// Inverse of the sRGB transfer function.
+fn srgb_to_linear(value: u8) -> f64 {
+    let c = value as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::fs;
+
+    // Writes solid faces of the given sizes and grey levels, returning their paths.
+    fn write_faces(name: &str, faces: [(u32, u8); 6]) -> Vec<String> {
+        faces
+            .iter()
+            .enumerate()
+            .map(|(i, &(size, level))| {
+                let path = env::temp_dir().join(format!(
+                    "cubemap-{}-{}-{}.jpg",
+                    name,
+                    std::process::id(),
+                    i
+                ));
+                let pixels = vec![level; (size * size * 3) as usize];
+                image::save_buffer(&path, &pixels, size, size, image::ColorType::Rgb8).unwrap();
+                path.to_str().unwrap().to_string()
+            })
+            .collect()
+    }
+
+    fn load(paths: &[String]) -> Result<Cubemap, ImageError> {
+        let result = Cubemap::new([
+            &paths[0], &paths[1], &paths[2], &paths[3], &paths[4], &paths[5],
+        ]);
+        for path in paths {
+            fs::remove_file(path).unwrap();
+        }
+        result
+    }
+
+    #[test]
+    fn faces_are_looked_up_by_major_axis_and_linearised() {
+        let levels = [0, 40, 80, 120, 188, 255];
+        let mut faces = [(8, 0); 6];
+        for (face, &level) in faces.iter_mut().zip(levels.iter()) {
+            face.1 = level;
+        }
+        let cubemap = load(&write_faces("axes", faces)).unwrap();
+
+        let directions = [
+            Vector::new(1.0, 0.2, -0.3),
+            Vector::new(-1.0, 0.4, 0.1),
+            Vector::new(0.3, 1.0, 0.2),
+            Vector::new(-0.2, -1.0, 0.4),
+            Vector::new(0.1, -0.3, 1.0),
+            Vector::new(0.2, 0.1, -1.0),
+        ];
+        for (&direction, &level) in directions.iter().zip(levels.iter()) {
+            let expected = srgb_to_linear(level);
+            let value = cubemap.value(direction);
+            assert!((value.x() - expected).abs() < 0.01, "{:?}", value);
+        }
+        assert!((srgb_to_linear(188) - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn faces_must_share_one_size() {
+        let mut faces = [(8, 128); 6];
+        faces[3].0 = 16;
+        assert!(load(&write_faces("sizes", faces)).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+use super::{luminance, Environment};
+use crate::core::{Color, Vector};
+use crate::objects::Sphere;
+
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+
+use image::hdr::HdrDecoder;
+use image::ImageError;
+use rand::prelude::*;
+
+// Equirectangular Radiance (.hdr) image wrapped around the scene, using the same
+// direction to (u, v) mapping as `Sphere`. Directions are drawn in proportion to
+// the luminance of each pixel, so small bright features like the sun are found
+// without relying on scattered rays hitting them.
+pub struct EnvironmentMap {
+    pixels: Vec<Color>,
+    width: usize,
+    height: usize,
+    // Rotation about the y axis in radians.
+    rotation: f64,
+    intensity: f64,
+    // Cumulative distribution over rows, then over the columns of each row.
+    marginal: Vec<f64>,
+    conditional: Vec<f64>,
+}
+
+impl EnvironmentMap {
+    pub fn new(filename: &str, rotation: f64, intensity: f64) -> Result<Self, ImageError> {
+        let decoder = HdrDecoder::new(BufReader::new(File::open(filename)?))?;
+        let metadata = decoder.metadata();
+        let (width, height) = (metadata.width as usize, metadata.height as usize);
+
+        let pixels = decoder
+            .read_image_hdr()?
+            .into_iter()
+            .map(|pixel| {
+                let [r, g, b] = pixel.0;
+                Color::new(r, g, b)
+            })
+            .collect();
+
+        Ok(Self::from_pixels(
+            pixels, width, height, rotation, intensity,
+        ))
+    }
+
+    // `pixels` are stored row by row starting from the top of the image.
+    pub fn from_pixels(
+        pixels: Vec<Color>,
+        width: usize,
+        height: usize,
+        rotation: f64,
+        intensity: f64,
+    ) -> Self {
+        assert_eq!(pixels.len(), width * height);
+
+        let mut conditional = Vec::with_capacity(width * height);
+        let mut rows = Vec::with_capacity(height);
+
+        for j in 0..height {
+            // Rows near the poles cover less solid angle.
+            let weight = latitude((j as f64 + 0.5) / height as f64).cos();
+            let row = &pixels[j * width..(j + 1) * width];
+            let (cdf, sum) = cumulative(row.iter().map(|&pixel| luminance(pixel) * weight));
+
+            conditional.extend(cdf);
+            rows.push(sum);
+        }
+
+        let (marginal, _) = cumulative(rows.into_iter());
+
+        Self {
+            pixels,
+            width,
+            height,
+            rotation,
+            intensity,
+            marginal,
+            conditional,
+        }
+    }
+
+    fn uv(&self, direction: Vector) -> (f64, f64) {
+        let d = rotate_y(direction.unit_vector(), -self.rotation);
+        let (mut u, mut v) = (0.0, 0.0);
+        Sphere::get_sphere_uv(d, &mut u, &mut v);
+        (u, v)
+    }
+
+    fn direction(&self, u: f64, v: f64) -> Vector {
+        let phi = (1.0 - u) * 2.0 * PI - PI;
+        let (sin_theta, cos_theta) = ((v - 0.5) * PI).sin_cos();
+        let d = Vector::new(cos_theta * phi.cos(), sin_theta, cos_theta * phi.sin());
+        rotate_y(d, self.rotation)
+    }
+
+    fn pixel(&self, u: f64, v: f64) -> (usize, usize) {
+        let i = ((u * self.width as f64) as usize).min(self.width - 1);
+        let j = (((1.0 - v) * self.height as f64) as usize).min(self.height - 1);
+        (i, j)
+    }
+
+    // Probability of picking pixel (i, j), times the number of pixels.
+    fn pixel_density(&self, i: usize, j: usize) -> f64 {
+        let row = &self.conditional[j * self.width..(j + 1) * self.width];
+        let p_row = self.marginal[j] - if j == 0 { 0.0 } else { self.marginal[j - 1] };
+        let p_column = row[i] - if i == 0 { 0.0 } else { row[i - 1] };
+        p_row * p_column * (self.width * self.height) as f64
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn value(&self, direction: Vector) -> Color {
+        let (u, v) = self.uv(direction);
+        let (i, j) = self.pixel(u, v);
+        self.pixels[j * self.width + i] * self.intensity
+    }
+
+    fn sample(&self, rng: &mut ThreadRng) -> Option<(Vector, f64)> {
+        let j = sample_index(&self.marginal, rng.gen());
+        let row = &self.conditional[j * self.width..(j + 1) * self.width];
+        let i = sample_index(row, rng.gen());
+
+        let u = (i as f64 + rng.gen::<f64>()) / self.width as f64;
+        let v = 1.0 - (j as f64 + rng.gen::<f64>()) / self.height as f64;
+
+        let cos_theta = latitude(1.0 - v).cos();
+        if cos_theta <= 0.0 {
+            return None;
+        }
+
+        let pdf = self.pixel_density(i, j) / (2.0 * PI * PI * cos_theta);
+        if pdf <= 0.0 {
+            return None;
+        }
+        Some((self.direction(u, v), pdf))
+    }
+
+    fn pdf(&self, direction: Vector) -> f64 {
+        let (u, v) = self.uv(direction);
+        let cos_theta = latitude(1.0 - v).cos();
+        if cos_theta <= 0.0 {
+            return 0.0;
+        }
+
+        let (i, j) = self.pixel(u, v);
+        self.pixel_density(i, j) / (2.0 * PI * PI * cos_theta)
+    }
+}
+
+// Latitude of a point `t` of the way down the image from its top edge.
+fn latitude(t: f64) -> f64 {
+    (0.5 - t) * PI
+}
+
+// Normalized running sum of `weights`, falling back to a uniform distribution
+// when they are all zero. Also returns the unnormalized total.
+fn cumulative<I: Iterator<Item = f64>>(weights: I) -> (Vec<f64>, f64) {
+    let mut total = 0.0;
+    let mut cdf: Vec<f64> = weights
+        .map(|w| {
+            total += w;
+            total
+        })
+        .collect();
+
+    let n = cdf.len() as f64;
+    for (k, c) in cdf.iter_mut().enumerate() {
+        *c = if total > 0.0 {
+            *c / total
+        } else {
+            (k + 1) as f64 / n
+        };
+    }
+    (cdf, total)
+}
+
+fn sample_index(cdf: &[f64], u: f64) -> usize {
+    cdf.partition_point(|&c| c <= u).min(cdf.len() - 1)
+}
+
+fn rotate_y(v: Vector, angle: f64) -> Vector {
+    let (sin, cos) = angle.sin_cos();
+    Vector::new(v.x() * cos + v.z() * sin, v.y(), -v.x() * sin + v.z() * cos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> EnvironmentMap {
+        let (width, height) = (8, 4);
+        let mut pixels = vec![Color::from(0.1); width * height];
+        pixels[width + 5] = Color::new(40.0, 30.0, 20.0);
+        pixels[3 * width + 2] = Color::from(5.0);
+        EnvironmentMap::from_pixels(pixels, width, height, 0.7, 1.0)
+    }
+
+    #[test]
+    fn cumulative_skips_zero_weights() {
+        let (cdf, total) = cumulative(vec![1.0, 0.0, 3.0].into_iter());
+        assert_eq!(total, 4.0);
+        assert_eq!(cdf, vec![0.25, 0.25, 1.0]);
+
+        assert_eq!(sample_index(&cdf, 0.0), 0);
+        assert_eq!(sample_index(&cdf, 0.2), 0);
+        assert_eq!(sample_index(&cdf, 0.25), 2);
+        assert_eq!(sample_index(&cdf, 0.999), 2);
+    }
+
+    #[test]
+    fn cumulative_falls_back_to_uniform() {
+        let (cdf, total) = cumulative(vec![0.0; 4].into_iter());
+        assert_eq!(total, 0.0);
+        assert_eq!(cdf, vec![0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn directions_round_trip_through_uv() {
+        let map = map();
+        for &(u, v) in &[(0.1, 0.2), (0.5, 0.5), (0.9, 0.8)] {
+            let (u2, v2) = map.uv(map.direction(u, v));
+            assert!((u - u2).abs() < 1e-9 && (v - v2).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn pixels_are_sampled_in_proportion_to_their_weight() {
+        let map = map();
+        let mut rng = rand::thread_rng();
+        let mut counts = vec![0; map.width * map.height];
+
+        let samples = 200_000;
+        for _ in 0..samples {
+            let (direction, pdf) = map.sample(&mut rng).unwrap();
+            // The density has a 1/cos(latitude) pole, where the round trip
+            // through (u, v) loses too many digits to compare.
+            if direction.y().abs() < 0.9999 {
+                assert!((pdf - map.pdf(direction)).abs() <= 1e-6 * pdf);
+            }
+
+            let (u, v) = map.uv(direction);
+            let (i, j) = map.pixel(u, v);
+            counts[j * map.width + i] += 1;
+        }
+
+        let pixels = (map.width * map.height) as f64;
+        for j in 0..map.height {
+            for i in 0..map.width {
+                let expected = map.pixel_density(i, j) / pixels;
+                let observed = counts[j * map.width + i] as f64 / samples as f64;
+                // Five standard deviations of the sampled fraction.
+                let tolerance = 5.0 * (expected / samples as f64).sqrt();
+                assert!(
+                    (observed - expected).abs() < tolerance,
+                    "pixel ({}, {}): {} against {}",
+                    i,
+                    j,
+                    observed,
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let map = map();
+        let (rows, columns) = (400, 800);
+        let mut total = 0.0;
+        for i in 0..rows {
+            let z = -1.0 + 2.0 * (i as f64 + 0.5) / rows as f64;
+            let r = (1.0 - z * z).sqrt();
+            for j in 0..columns {
+                let phi = 2.0 * PI * (j as f64 + 0.5) / columns as f64;
+                total += map.pdf(Vector::new(r * phi.cos(), z, r * phi.sin()));
+            }
+        }
+        total *= 4.0 * PI / (rows * columns) as f64;
+
+        assert!((total - 1.0).abs() < 0.01, "pdf integrates to {}", total);
+    }
+}
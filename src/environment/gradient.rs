@@ -0,0 +1,26 @@
+use super::Environment;
+use crate::core::{Color, Vector};
+
+// Blend from `bottom` straight down to `top` straight up, linear in the height
+// of the direction, like the sky of "Ray Tracing in One Weekend".
+pub struct Gradient {
+    bottom: Color,
+    top: Color,
+}
+
+impl Gradient {
+    pub fn new(bottom: Color, top: Color) -> Self {
+        Self { bottom, top }
+    }
+
+    pub fn sky() -> Self {
+        Self::new(Color::from(1), Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Environment for Gradient {
+    fn value(&self, direction: Vector) -> Color {
+        let t = 0.5 * (direction.unit_vector().y() + 1.0);
+        self.bottom * (1.0 - t) + self.top * t
+    }
+}
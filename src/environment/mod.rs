@@ -0,0 +1,42 @@
+mod cubemap;
+mod environment_map;
+mod gradient;
+mod sky;
+mod spherical_texture;
+
+use crate::core::{Color, Vector};
+
+pub use cubemap::Cubemap;
+pub use environment_map::EnvironmentMap;
+pub use gradient::Gradient;
+pub use sky::Sky;
+pub use spherical_texture::SphericalTexture;
+
+use rand::prelude::*;
+
+// Radiance arriving from infinitely far away, looked up by direction when a ray
+// leaves the scene.
+pub trait Environment: Send + Sync {
+    fn value(&self, direction: Vector) -> Color;
+
+    // Environments that can be importance sampled return a unit direction and
+    // its solid-angle density.
+    fn sample(&self, _rng: &mut ThreadRng) -> Option<(Vector, f64)> {
+        None
+    }
+
+    fn pdf(&self, _direction: Vector) -> f64 {
+        0.0
+    }
+}
+
+// A constant background, the same in every direction.
+impl Environment for Color {
+    fn value(&self, _direction: Vector) -> Color {
+        *self
+    }
+}
+
+fn luminance(color: Color) -> f64 {
+    color.dot(Color::new(0.2126, 0.7152, 0.0722))
+}
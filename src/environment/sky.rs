@@ -0,0 +1,303 @@
+use super::{luminance, Environment};
+use crate::core::{Color, Onb, Vector};
+use crate::spectrum::xyz_to_rgb;
+use crate::utils::clamp;
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use rand::prelude::*;
+
+// Angular radius of the sun seen from the earth, in radians.
+const SUN_ANGULAR_RADIUS: f64 = 0.004_654;
+
+// Luminance of the sun outside the atmosphere in kcd/m², the unit of the sky model.
+const SUN_LUMINANCE: f64 = 1.88e6;
+
+// Wavelengths in micrometres that stand in for the red, green and blue channels
+// when attenuating sunlight.
+const SUN_WAVELENGTHS: [f64; 3] = [0.68, 0.55, 0.44];
+
+// Clear sky model of Preetham, Shirley and Smits 1999, with a sun disk of the
+// correct angular size seen through the same atmosphere. Radiance is in kcd/m²
+// times `intensity`; nothing is emitted from below the horizon.
+pub struct Sky {
+    sun: Onb,
+    sun_cos_max: f64,
+    sun_radiance: Color,
+    // Chance of sampling the sun disk rather than the sky dome.
+    sun_probability: f64,
+    // Perez coefficients A to E for luminance and the two chromaticities.
+    perez: [[f64; 5]; 3],
+    // Luminance and chromaticity divided by the Perez function at the zenith.
+    zenith: [f64; 3],
+    intensity: f64,
+}
+
+impl Sky {
+    // `turbidity` ranges from 2 for a very clear sky to about 10 for a hazy one.
+    pub fn new(sun_direction: Vector, turbidity: f64, intensity: f64) -> Self {
+        let t = turbidity;
+        let sun_direction = sun_direction.unit_vector();
+        // The model is only defined for a sun above the horizon.
+        let theta_s = clamp(sun_direction.y(), 0.0, 1.0).acos();
+
+        let perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+
+        let (theta, theta2, theta3) = (theta_s, theta_s * theta_s, theta_s * theta_s * theta_s);
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let row = |r: [f64; 4]| r[0] * theta3 + r[1] * theta2 + r[2] * theta + r[3];
+            t * t * row(m[0]) + t * row(m[1]) + row(m[2])
+        };
+        let zenith_x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let zenith_y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+
+        let mut zenith = [zenith_luminance, zenith_x, zenith_y];
+        for (value, coefficients) in zenith.iter_mut().zip(perez.iter()) {
+            *value /= perez_function(coefficients, 1.0, theta_s);
+        }
+
+        let sun_radiance = if sun_direction.y() > 0.0 {
+            sun_transmittance(theta_s, t) * SUN_LUMINANCE
+        } else {
+            Color::from(0)
+        };
+
+        let sun_cos_max = SUN_ANGULAR_RADIUS.cos();
+        let sun_power = luminance(sun_radiance) * 2.0 * PI * (1.0 - sun_cos_max);
+        let sky_power = zenith_luminance.max(0.0) * PI;
+        let sun_probability = if sun_power > 0.0 {
+            clamp(sun_power / (sun_power + sky_power), 0.1, 0.9)
+        } else {
+            0.0
+        };
+
+        Self {
+            sun: Onb::from_w(sun_direction),
+            sun_cos_max,
+            sun_radiance,
+            sun_probability,
+            perez,
+            zenith,
+            intensity,
+        }
+    }
+
+    fn sky(&self, direction: Vector) -> Color {
+        let cos_theta = direction.y().max(1e-3);
+        let gamma = clamp(direction.dot(self.sun.w()), -1.0, 1.0).acos();
+
+        let value = |i: usize| self.zenith[i] * perez_function(&self.perez[i], cos_theta, gamma);
+        let (luminance, x, y) = (value(0), value(1), value(2));
+        if luminance <= 0.0 || y <= 0.0 {
+            return Color::from(0);
+        }
+
+        let xyz = Vector::new(x * luminance / y, luminance, (1.0 - x - y) * luminance / y);
+        let rgb = xyz_to_rgb(xyz);
+        Color::new(rgb.x().max(0.0), rgb.y().max(0.0), rgb.z().max(0.0))
+    }
+
+    fn in_sun(&self, direction: Vector) -> bool {
+        direction.dot(self.sun.w()) >= self.sun_cos_max
+    }
+}
+
+impl Environment for Sky {
+    fn value(&self, direction: Vector) -> Color {
+        let direction = direction.unit_vector();
+        if direction.y() <= 0.0 {
+            return Color::from(0);
+        }
+
+        let mut radiance = self.sky(direction);
+        if self.in_sun(direction) {
+            radiance += self.sun_radiance;
+        }
+        radiance * self.intensity
+    }
+
+    fn sample(&self, rng: &mut ThreadRng) -> Option<(Vector, f64)> {
+        let direction = if rng.gen::<f64>() < self.sun_probability {
+            let cos_theta = 1.0 - rng.gen::<f64>() * (1.0 - self.sun_cos_max);
+            let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+            let phi = 2.0 * PI * rng.gen::<f64>();
+            self.sun.local(Vector::new(
+                sin_theta * phi.cos(),
+                sin_theta * phi.sin(),
+                cos_theta,
+            ))
+        } else {
+            let d = Vector::random_cosine_direction(rng);
+            Vector::new(d.x(), d.z(), d.y())
+        };
+
+        let pdf = self.pdf(direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+        Some((direction, pdf))
+    }
+
+    fn pdf(&self, direction: Vector) -> f64 {
+        let direction = direction.unit_vector();
+        if direction.y() <= 0.0 {
+            return 0.0;
+        }
+
+        let mut pdf = (1.0 - self.sun_probability) * direction.y() / PI;
+        if self.in_sun(direction) {
+            pdf += self.sun_probability / (2.0 * PI * (1.0 - self.sun_cos_max));
+        }
+        pdf
+    }
+}
+
+// Perez et al. 1993 sky luminance distribution, for a view direction at angle
+// theta from the zenith and gamma from the sun.
+fn perez_function(c: &[f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+    let cos_gamma = gamma.cos();
+    (1.0 + c[0] * (c[1] / cos_theta).exp())
+        * (1.0 + c[2] * (c[3] * gamma).exp() + c[4] * cos_gamma * cos_gamma)
+}
+
+// Rayleigh and aerosol extinction of direct sunlight along the path through the
+// atmosphere, following the appendix of Preetham et al.
+fn sun_transmittance(theta_s: f64, turbidity: f64) -> Color {
+    let degrees = theta_s.to_degrees().min(FRAC_PI_2.to_degrees());
+    let optical_mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - degrees).powf(-1.253));
+    // Ångström's turbidity coefficient, with a wavelength exponent of 1.3.
+    let beta = 0.04608 * turbidity - 0.04586;
+
+    let channel = |i: usize| {
+        let lambda: f64 = SUN_WAVELENGTHS[i];
+        let rayleigh = 0.008735 * lambda.powf(-4.08);
+        let aerosol = beta * lambda.powf(-1.3);
+        (-(rayleigh + aerosol) * optical_mass).exp()
+    };
+    Color::new(channel(0), channel(1), channel(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sky() -> Sky {
+        Sky::new(Vector::new(1.0, 0.6, 0.5), 3.0, 1.0)
+    }
+
+    #[test]
+    fn samples_follow_the_pdf() {
+        let sky = sky();
+        let mut rng = rand::thread_rng();
+
+        // Elevation bands of the dome, where the cosine-weighted part puts
+        // y1² - y0² of its samples, and the sun disk on its own.
+        let bands = 10;
+        let mut counts = vec![0; bands];
+        let mut in_sun = 0;
+
+        let samples = 200_000;
+        for _ in 0..samples {
+            let (direction, pdf) = sky.sample(&mut rng).unwrap();
+            assert!((pdf - sky.pdf(direction)).abs() <= 1e-9 * pdf);
+
+            if sky.in_sun(direction) {
+                in_sun += 1;
+            } else {
+                let band = (direction.y() * bands as f64) as usize;
+                counts[band.min(bands - 1)] += 1;
+            }
+        }
+
+        let within = |observed: usize, expected: f64| {
+            let observed = observed as f64 / samples as f64;
+            // Five standard deviations of the sampled fraction.
+            let tolerance = 5.0 * (expected / samples as f64).sqrt();
+            assert!(
+                (observed - expected).abs() < tolerance,
+                "{} against {}",
+                observed,
+                expected
+            );
+        };
+
+        // The dome's share of the disk is a cosine times its tiny solid angle.
+        let disk = 2.0 * PI * (1.0 - sky.sun_cos_max);
+        let sun_elevation = sky.sun.w().y();
+        within(
+            in_sun,
+            sky.sun_probability + (1.0 - sky.sun_probability) * sun_elevation * disk / PI,
+        );
+        for (i, &count) in counts.iter().enumerate() {
+            let (y0, y1) = (i as f64 / bands as f64, (i + 1) as f64 / bands as f64);
+            let mut expected = (1.0 - sky.sun_probability) * (y1 * y1 - y0 * y0);
+            if (y0..y1).contains(&sun_elevation) {
+                expected -= (1.0 - sky.sun_probability) * sun_elevation * disk / PI;
+            }
+            within(count, expected);
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let sky = sky();
+
+        // Midpoint rule in the sun's frame, finely over the disk and coarsely
+        // beyond it.
+        let theta_sun = sky.sun_cos_max.acos();
+        let segments = [(0.0, 2.0 * theta_sun, 200), (2.0 * theta_sun, PI, 1000)];
+        let columns = 1000;
+        let mut total = 0.0;
+        for &(start, end, rows) in segments.iter() {
+            let d_theta = (end - start) / rows as f64;
+            for i in 0..rows {
+                let (sin_theta, cos_theta) = (start + (i as f64 + 0.5) * d_theta).sin_cos();
+                for j in 0..columns {
+                    let phi = 2.0 * PI * (j as f64 + 0.5) / columns as f64;
+                    let direction = sky.sun.local(Vector::new(
+                        sin_theta * phi.cos(),
+                        sin_theta * phi.sin(),
+                        cos_theta,
+                    ));
+                    total += sky.pdf(direction) * sin_theta * d_theta;
+                }
+            }
+        }
+        total *= 2.0 * PI / columns as f64;
+
+        assert!((total - 1.0).abs() < 1e-3, "pdf integrates to {}", total);
+    }
+}
@@ -0,0 +1,27 @@
+use super::Environment;
+use crate::core::{Color, Vector};
+use crate::objects::Sphere;
+use crate::textures::Texture;
+
+// Any texture wrapped around the scene with the (u, v) mapping of `Sphere`. The
+// unit direction is passed as the texture's point, so solid textures work too.
+// Texture values are used as linear radiance, so an `Image` texture, which
+// returns its stored 8-bit values, should hold linear rather than sRGB data.
+pub struct SphericalTexture {
+    texture: Texture,
+}
+
+impl SphericalTexture {
+    pub fn new(texture: Texture) -> Self {
+        Self { texture }
+    }
+}
+
+impl Environment for SphericalTexture {
+    fn value(&self, direction: Vector) -> Color {
+        let direction = direction.unit_vector();
+        let (mut u, mut v) = (0.0, 0.0);
+        Sphere::get_sphere_uv(direction, &mut u, &mut v);
+        self.texture.value(u, v, direction)
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Integrator, Radiance};
+use crate::core::{Color, Ray};
+use crate::environment::Environment;
+use crate::lights::Light;
+use crate::objects::{HitRecord, Object};
+
+use rand::prelude::*;
+
+#[derive(Copy, Clone, Debug)]
+pub enum DebugMode {
+    Albedo,
+    BvhNodes { max_nodes: usize },
+    Depth { max_t: f64 },
+    FrontFace,
+    Normal,
+    Uv,
+}
+
+pub struct DebugIntegrator {
+    mode: DebugMode,
+}
+
+impl DebugIntegrator {
+    pub fn new(mode: DebugMode) -> Self {
+        Self { mode }
+    }
+}
+
+// Debug integrators don't follow paths, so everything they return is reported
+// as direct light.
+impl Integrator for DebugIntegrator {
+    fn li_split(
+        &self,
+        ray: &Ray,
+        world: &dyn Object,
+        _lights: &[Light],
+        _environment: &dyn Environment,
+        first_hit: &mut dyn FnMut(&Ray, &HitRecord),
+        rng: &mut ThreadRng,
+    ) -> Radiance {
+        let mut nodes = 0;
+        let hit = world.hit_counting_nodes(ray, 0.001..f64::INFINITY, rng, &mut nodes);
+        if let Some(rec) = &hit {
+            first_hit(ray, rec);
+        }
+
+        let direct = match (self.mode, hit) {
+            (DebugMode::BvhNodes { max_nodes }, _) => {
+                let visited = nodes as f64 / max_nodes as f64;
+                Color::new(visited, visited, 0.0)
+            }
+            (_, None) => Color::from(0),
+            (DebugMode::Albedo, Some(rec)) => rec.material.albedo(&rec),
+            (DebugMode::Depth { max_t }, Some(rec)) => {
+                Color::from(1.0 - (rec.t * ray.direction.length() / max_t).min(1.0))
+            }
+            (DebugMode::FrontFace, Some(rec)) => {
+                if rec.front_face {
+                    Color::new(0, 1, 0)
+                } else {
+                    Color::new(1, 0, 0)
+                }
+            }
+            (DebugMode::Normal, Some(rec)) => (rec.normal + Color::from(1)) * 0.5,
+            (DebugMode::Uv, Some(rec)) => Color::new(rec.u, rec.v, 0.0),
+        };
+
+        Radiance {
+            direct,
+            ..Radiance::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Point, Vector};
+    use crate::materials::Material;
+    use crate::objects::{Bvh, Sphere, Translate};
+    use crate::textures::SolidColor;
+
+    fn sphere(center: Point) -> Sphere {
+        let material = Material::Lambertian {
+            albedo: SolidColor::from(0.5),
+        };
+        Sphere::new(center, 1.0, material)
+    }
+
+    fn spheres(count: i32) -> Vec<Box<dyn Object>> {
+        (0..count)
+            .map(|i| Box::new(sphere(Point::new(3 * i, 0, 0))) as Box<dyn Object>)
+            .collect()
+    }
+
+    fn render(mode: DebugMode, world: &dyn Object, ray: &Ray) -> Color {
+        let integrator = DebugIntegrator::new(mode);
+        integrator.li(ray, world, &[], &Color::from(0), &mut rand::thread_rng())
+    }
+
+    fn assert_close(a: Color, b: Color) {
+        assert!((a - b).length() < 1e-12, "{:?} != {:?}", a, b);
+    }
+
+    fn towards_z(origin: Point) -> Ray {
+        Ray::new(origin, Vector::new(0, 0, 1), 0.0)
+    }
+
+    #[test]
+    fn surface_modes_show_the_first_hit() {
+        let world = sphere(Point::new(0, 0, 0));
+        let outside = towards_z(Point::new(0, 0, -5));
+
+        let normal = render(DebugMode::Normal, &world, &outside);
+        assert_close(normal, Color::new(0.5, 0.5, 0.0));
+
+        let depth = render(DebugMode::Depth { max_t: 8.0 }, &world, &outside);
+        assert_close(depth, Color::from(0.5));
+
+        let front = render(DebugMode::FrontFace, &world, &outside);
+        assert_close(front, Color::new(0, 1, 0));
+        let inside = towards_z(Point::new(0, 0, 0));
+        let back = render(DebugMode::FrontFace, &world, &inside);
+        assert_close(back, Color::new(1, 0, 0));
+
+        let missed = towards_z(Point::new(5, 0, -5));
+        assert_close(render(DebugMode::Normal, &world, &missed), Color::from(0));
+    }
+
+    #[test]
+    fn bvh_nodes_counts_the_nodes_each_query_visits() {
+        let mut rng = rand::thread_rng();
+        let world = Bvh::new(spheres(8), 0.0..1.0, &mut rng);
+        let mode = DebugMode::BvhNodes { max_nodes: 100 };
+        let nodes = |ray: &Ray| render(mode, &world, ray).x() * 100.0;
+
+        // A ray missing the root's bounds stops there.
+        let missed = towards_z(Point::new(0, 5, -5));
+        assert!((nodes(&missed) - 1.0).abs() < 1e-9);
+
+        // Counts start afresh for every query, and never exceed the tree's 15 nodes.
+        let ray = towards_z(Point::new(9, 0, -5));
+        let first = nodes(&ray);
+        assert!(first > 1.0 && first <= 15.0, "{}", first);
+        assert_eq!(nodes(&ray), first);
+
+        let unstructured = sphere(Point::new(0, 0, 0));
+        assert_close(render(mode, &unstructured, &ray), Color::from(0));
+    }
+
+    #[test]
+    fn bvh_nodes_counts_hierarchies_inside_transforms() {
+        let mut rng = rand::thread_rng();
+        let inner = Bvh::new(spheres(4), 0.0..1.0, &mut rng);
+        let moved: Box<dyn Object> = Box::new(Translate::new(inner, Vector::new(0, 10, 0)));
+        let world = Bvh::new(vec![moved], 0.0..1.0, &mut rng);
+
+        let ray = towards_z(Point::new(0, 10, -5));
+        let nodes = render(DebugMode::BvhNodes { max_nodes: 100 }, &world, &ray).x() * 100.0;
+        assert!(nodes > 2.0, "{}", nodes);
+    }
+}
@@ -0,0 +1,92 @@
+mod debug;
+mod path;
+
+use crate::core::{Color, Ray};
+use crate::environment::Environment;
+use crate::lights::Light;
+use crate::objects::{HitRecord, Object};
+
+pub use debug::{DebugIntegrator, DebugMode};
+pub use path::PathTracer;
+
+use rand::prelude::*;
+
+pub trait Integrator: Send + Sync {
+    fn li(
+        &self,
+        ray: &Ray,
+        world: &dyn Object,
+        lights: &[Light],
+        environment: &dyn Environment,
+        rng: &mut ThreadRng,
+    ) -> Color {
+        self.li_split(ray, world, lights, environment, &mut |_, _| {}, rng)
+            .total()
+    }
+
+    // `first_hit` is called with the camera ray and the first surface it hits,
+    // so per-pixel outputs can be taken from the same trace as the radiance.
+    fn li_split(
+        &self,
+        ray: &Ray,
+        world: &dyn Object,
+        lights: &[Light],
+        environment: &dyn Environment,
+        first_hit: &mut dyn FnMut(&Ray, &HitRecord),
+        rng: &mut ThreadRng,
+    ) -> Radiance;
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Radiance {
+    pub emission: Color,
+    pub direct: Color,
+    pub indirect: Color,
+}
+
+impl Radiance {
+    pub fn total(&self) -> Color {
+        self.emission + self.direct + self.indirect
+    }
+
+    pub fn add(&mut self, depth: u32, value: Color) {
+        match depth {
+            0 => self.emission += value,
+            1 => self.direct += value,
+            _ => self.indirect += value,
+        }
+    }
+}
+
+impl Default for Radiance {
+    fn default() -> Self {
+        Self {
+            emission: Color::from(0),
+            direct: Color::from(0),
+            indirect: Color::from(0),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum IntegratorKind {
+    Debug(DebugMode),
+    Path {
+        max_depth: u32,
+        rr_min_depth: u32,
+        spectral: bool,
+    },
+}
+
+impl IntegratorKind {
+    pub fn build(&self) -> Box<dyn Integrator> {
+        match *self {
+            IntegratorKind::Debug(mode) => Box::new(DebugIntegrator::new(mode)),
+            IntegratorKind::Path {
+                max_depth,
+                rr_min_depth,
+                spectral,
+            } => Box::new(PathTracer::new(max_depth, rr_min_depth, spectral)),
+        }
+    }
+}
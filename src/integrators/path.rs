@@ -0,0 +1,346 @@
+use super::{Integrator, Radiance};
+use crate::core::{Color, Point, Ray, Vector};
+use crate::environment::Environment;
+use crate::lights::Light;
+use crate::objects::{HitRecord, Object};
+use crate::spectrum::Wavelengths;
+
+use rand::prelude::*;
+
+pub struct PathTracer {
+    max_depth: u32,
+    rr_min_depth: u32,
+    spectral: bool,
+}
+
+impl PathTracer {
+    pub fn new(max_depth: u32, rr_min_depth: u32, spectral: bool) -> Self {
+        Self {
+            max_depth,
+            rr_min_depth,
+            spectral,
+        }
+    }
+}
+
+// In spectral mode path quantities hold one value per sampled wavelength instead of RGB.
+fn uplift(wavelengths: &Option<Wavelengths>, rgb: Color) -> Color {
+    match wavelengths {
+        Some(wavelengths) => wavelengths.uplift(rgb),
+        None => rgb,
+    }
+}
+
+// Veach's power heuristic for combining two sampling strategies.
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+    if a + b > 0.0 {
+        a / (a + b)
+    } else {
+        0.0
+    }
+}
+
+// Whether anything lies between `origin` and the point `distance` away along the
+// unit `direction`, tracing at the wavelength of `ray`.
+fn unoccluded(
+    world: &dyn Object,
+    ray: &Ray,
+    origin: Point,
+    direction: Vector,
+    distance: f64,
+    rng: &mut ThreadRng,
+) -> bool {
+    let shadow = Ray {
+        wavelength: ray.wavelength,
+        ..Ray::new(origin, direction, ray.time)
+    };
+    world.hit(&shadow, 0.001..distance - 0.001, rng).is_none()
+}
+
+impl Integrator for PathTracer {
+    fn li_split(
+        &self,
+        ray: &Ray,
+        world: &dyn Object,
+        lights: &[Light],
+        environment: &dyn Environment,
+        first_hit: &mut dyn FnMut(&Ray, &HitRecord),
+        rng: &mut ThreadRng,
+    ) -> Radiance {
+        let mut radiance = Radiance::default();
+        let mut throughput = Color::from(1);
+        // Density the material's non-delta lobes give the scattered direction, or
+        // None if it came from the camera or a delta lobe. Light sampling can
+        // never produce a delta direction, so that light is counted in full.
+        let mut bsdf_pdf: Option<f64> = None;
+
+        let mut wavelengths = if self.spectral {
+            Some(Wavelengths::sample(rng))
+        } else {
+            None
+        };
+        let mut ray = Ray {
+            wavelength: wavelengths.map(|w| w.hero()),
+            ..*ray
+        };
+
+        for depth in 0..self.max_depth {
+            let rec = match world.hit(&ray, 0.001..f64::INFINITY, rng) {
+                Some(rec) => rec,
+                None => {
+                    let direction = ray.direction.unit_vector();
+                    let weight = match bsdf_pdf {
+                        Some(pdf) => power_heuristic(pdf, environment.pdf(direction)),
+                        None => 1.0,
+                    };
+                    let background = environment.value(direction);
+                    radiance.add(
+                        depth,
+                        throughput * uplift(&wavelengths, background) * weight,
+                    );
+                    break;
+                }
+            };
+
+            if depth == 0 {
+                first_hit(&ray, &rec);
+            }
+
+            let emitted = match &wavelengths {
+                Some(wavelengths) => rec.material.emitted_spectrum(&ray, &rec, wavelengths),
+                None => rec.material.emitted(&ray, &rec),
+            };
+            radiance.add(depth, throughput * emitted);
+
+            let srec = rec.material.scatter(&ray, &rec, rng);
+
+            if rec.material.is_dispersive() {
+                if let Some(wavelengths) = wavelengths.as_mut() {
+                    wavelengths.terminate_secondary();
+                }
+            }
+
+            // Lights are sampled against every non-delta lobe of the material,
+            // whether or not the scatter above picked one or found a direction at
+            // all. They are only sampled where the path could still continue, so
+            // that paths end at the same length whichever way they reach a light.
+            if rec.material.has_non_delta(&rec) && depth + 1 < self.max_depth {
+                if let Some((direction, light_pdf)) = environment.sample(rng) {
+                    let bsdf = rec.material.eval(&ray, &rec, direction);
+
+                    if bsdf.max_component() > 0.0
+                        && unoccluded(world, &ray, rec.p, direction, f64::INFINITY, rng)
+                    {
+                        let pdf = rec.material.pdf(&ray, &rec, direction);
+                        let weight = power_heuristic(light_pdf, pdf) / light_pdf;
+                        let light = uplift(&wavelengths, environment.value(direction));
+                        let bsdf = uplift(&wavelengths, bsdf * weight);
+                        radiance.add(depth + 1, throughput * bsdf * light);
+                    }
+                }
+
+                for light in lights {
+                    let (direction, distance, irradiance) = match light.illuminate(rec.p) {
+                        Some(sample) => sample,
+                        None => continue,
+                    };
+                    let bsdf = rec.material.eval(&ray, &rec, direction);
+
+                    if bsdf.max_component() > 0.0
+                        && unoccluded(world, &ray, rec.p, direction, distance, rng)
+                    {
+                        let light = uplift(&wavelengths, irradiance);
+                        radiance.add(depth + 1, throughput * uplift(&wavelengths, bsdf) * light);
+                    }
+                }
+            }
+
+            let srec = match srec {
+                Some(srec) => srec,
+                None => break,
+            };
+
+            bsdf_pdf = if srec.specular { None } else { Some(srec.pdf) };
+            throughput *= uplift(&wavelengths, srec.weight());
+
+            if depth >= self.rr_min_depth {
+                let survival = throughput.max_component().min(1.0);
+                if rng.gen::<f64>() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+
+            ray = Ray {
+                wavelength: srec.ray.wavelength.or(ray.wavelength),
+                ..srec.ray
+            };
+        }
+
+        match wavelengths {
+            Some(wavelengths) => Radiance {
+                emission: wavelengths.to_rgb(radiance.emission),
+                direct: wavelengths.to_rgb(radiance.direct),
+                indirect: wavelengths.to_rgb(radiance.indirect),
+            },
+            None => radiance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::EnvironmentMap;
+    use crate::materials::{Coated, DiffuseLight, Material, Mix};
+    use crate::objects::{Sphere, XZRect};
+    use crate::textures::SolidColor;
+
+    use std::f64::consts::PI;
+
+    // Average radiance looking straight down at a large floor of `material`, with
+    // nothing else in the scene. Every path that leaves the floor escapes.
+    fn looking_down(
+        material: Material,
+        lights: &[Light],
+        environment: &dyn Environment,
+        samples: usize,
+    ) -> f64 {
+        let floor = XZRect::new(-100.0, 100.0, -100.0, 100.0, 0.0, material);
+        let integrator = PathTracer::new(10, 3, false);
+        let ray = Ray::new(Point::new(0, 1, 0), Vector::new(0, -1, 0), 0.0);
+        let mut rng = rand::thread_rng();
+
+        let total: f64 = (0..samples)
+            .map(|_| {
+                integrator
+                    .li(&ray, &floor, lights, environment, &mut rng)
+                    .y()
+            })
+            .sum();
+        total / samples as f64
+    }
+
+    // Lit only by a unit directional light shining straight down into a black sky,
+    // so every sample is the same direct light.
+    fn lit_from_above(material: Material) -> f64 {
+        let lights = [Light::directional(Vector::new(0, -1, 0), Color::from(1))];
+        looking_down(material, &lights, &Color::from(0), 100)
+    }
+
+    // Average radiance seen from inside a closed sphere whose walls emit 1 and
+    // scatter with `albedo`, blended 1 : 9. A path is only carried on by the
+    // scattering part, so each bounce passes on 0.9 times the albedo and the
+    // radiance is the series 0.1 * Σ (0.9 * albedo)^k over the bounces traced.
+    fn furnace(integrator: &PathTracer, albedo: f64, samples: usize) -> (f64, f64) {
+        let walls = Mix::new_material(
+            Material::Lambertian {
+                albedo: SolidColor::from(albedo),
+            },
+            DiffuseLight::new_material(SolidColor::from(1)),
+            SolidColor::from(0.1),
+        );
+        let enclosure = Sphere::new(Point::from(0), 1.0, walls);
+        let mut rng = rand::thread_rng();
+
+        let total: f64 = (0..samples)
+            .map(|_| {
+                let ray = Ray::new(Point::from(0), Vector::random_unit_vector(&mut rng), 0.0);
+                integrator
+                    .li(&ray, &enclosure, &[], &Color::from(0), &mut rng)
+                    .y()
+            })
+            .sum();
+
+        let q = 0.9 * albedo;
+        let expected = 0.1 * (1.0 - q.powi(integrator.max_depth as i32)) / (1.0 - q);
+        (total / samples as f64, expected)
+    }
+
+    #[test]
+    fn closed_furnace_converges_without_roulette() {
+        let integrator = PathTracer::new(50, 50, false);
+        for &albedo in [0.5, 0.8, 1.0].iter() {
+            let (radiance, expected) = furnace(&integrator, albedo, 20_000);
+            assert!(
+                (radiance - expected).abs() < 0.01 * expected,
+                "albedo {}: {} != {}",
+                albedo,
+                radiance,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn closed_furnace_converges_with_roulette() {
+        // Roulette starts long before max_depth, so it ends nearly every path.
+        let integrator = PathTracer::new(50, 3, false);
+        for &albedo in [0.5, 0.8].iter() {
+            let (radiance, expected) = furnace(&integrator, albedo, 20_000);
+            assert!(
+                (radiance - expected).abs() < 0.01 * expected,
+                "albedo {}: {} != {}",
+                albedo,
+                radiance,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn smooth_coated_sees_delta_lights_through_its_base() {
+        let radiance = lit_from_above(Coated::new_material(SolidColor::from(0.5), 1.5, 0.0));
+
+        // The coat's diffuse term with both directions along the normal.
+        let f0 = 0.04;
+        let weight = 1.0 - 0.5f64.powi(5);
+        let expected = 0.5 * 28.0 / (23.0 * PI) * (1.0 - f0) * weight * weight;
+        assert!(
+            (radiance - expected).abs() < 1e-6,
+            "{} != {}",
+            radiance,
+            expected
+        );
+    }
+
+    #[test]
+    fn mix_with_a_mirror_sees_delta_lights_through_its_diffuse_half() {
+        let diffuse = Material::Lambertian {
+            albedo: SolidColor::from(0.5),
+        };
+        let mirror = Material::Metal {
+            albedo: Color::from(1),
+            fuzz: 0.0,
+        };
+        let radiance = lit_from_above(Mix::new_material(diffuse, mirror, SolidColor::from(0.5)));
+
+        let expected = 0.5 * 0.5 / PI;
+        assert!(
+            (radiance - expected).abs() < 1e-6,
+            "{} != {}",
+            radiance,
+            expected
+        );
+    }
+
+    #[test]
+    fn smooth_coated_reflects_its_albedo_under_a_uniform_environment_map() {
+        let material = Coated::new_material(SolidColor::from(0.5), 1.5, 0.0);
+        let environment = EnvironmentMap::from_pixels(vec![Color::from(1); 32], 8, 4, 0.0, 1.0);
+        let radiance = looking_down(material, &[], &environment, 20_000);
+
+        // The coat's reflectance at normal incidence, plus the base's albedo,
+        // which the 28/23π normalization makes (1 - f0) times the albedo times
+        // the outgoing weight.
+        let f0 = 0.04;
+        let expected = f0 + (1.0 - f0) * 0.5 * (1.0 - 0.5f64.powi(5));
+        assert!(
+            (radiance - expected).abs() < 0.01 * expected,
+            "{} != {}",
+            radiance,
+            expected
+        );
+    }
+}
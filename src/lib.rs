@@ -1,6 +1,11 @@
 pub mod core;
+pub mod environment;
+pub mod integrators;
+pub mod lights;
 pub mod materials;
 pub mod objects;
+pub mod render;
 pub mod scene;
+pub mod spectrum;
 pub mod textures;
 pub mod utils;
@@ -0,0 +1,94 @@
+mod spot;
+
+use crate::core::{Color, Point, Vector};
+
+pub use spot::Spot;
+
+// Lights with no area, which scattered rays can never hit and so have to be
+// sampled explicitly at every shading point.
+#[derive(Clone)]
+pub enum Light {
+    // Infinitely far away, shining along the unit vector `direction` with the
+    // given irradiance.
+    Directional {
+        direction: Vector,
+        irradiance: Color,
+    },
+    // Radiant intensity emitted equally in all directions.
+    Point {
+        position: Point,
+        intensity: Color,
+    },
+    Spot(Spot),
+}
+
+impl Light {
+    pub fn point(position: Point, intensity: Color) -> Self {
+        Light::Point {
+            position,
+            intensity,
+        }
+    }
+
+    pub fn directional(direction: Vector, irradiance: Color) -> Self {
+        Light::Directional {
+            direction: direction.unit_vector(),
+            irradiance,
+        }
+    }
+
+    // Unit direction from `p` towards the light, the distance to it and the
+    // irradiance it delivers at `p` on a surface facing it.
+    pub fn illuminate(&self, p: Point) -> Option<(Vector, f64, Color)> {
+        match self {
+            Light::Directional {
+                direction,
+                irradiance,
+            } => Some((-*direction, f64::INFINITY, *irradiance)),
+            Light::Point {
+                position,
+                intensity,
+            } => {
+                let to_light = *position - p;
+                let distance_squared = to_light.length_squared();
+                if distance_squared <= 0.0 {
+                    return None;
+                }
+
+                let distance = distance_squared.sqrt();
+                Some((to_light / distance, distance, *intensity / distance_squared))
+            }
+            Light::Spot(s) => s.illuminate(p),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_lights_fall_off_with_the_square_of_distance() {
+        let light = Light::point(Point::new(0, 4, 0), Color::from(8));
+        let (direction, distance, irradiance) = light.illuminate(Point::new(0, 2, 0)).unwrap();
+
+        assert!((direction - Vector::new(0, 1, 0)).length() < 1e-12);
+        assert_eq!(distance, 2.0);
+        assert!((irradiance - Color::from(2)).length() < 1e-12);
+    }
+
+    #[test]
+    fn directional_lights_are_normalised_once() {
+        let light = Light::directional(Vector::new(0, -3, 4), Color::from(1));
+        match &light {
+            Light::Directional { direction, .. } => {
+                assert!((direction.length() - 1.0).abs() < 1e-12)
+            }
+            _ => unreachable!(),
+        }
+
+        let (direction, distance, _) = light.illuminate(Point::from(0)).unwrap();
+        assert!((direction - Vector::new(0.0, 0.6, -0.8)).length() < 1e-12);
+        assert_eq!(distance, f64::INFINITY);
+    }
+}
@@ -0,0 +1,202 @@
+use super::Light;
+use crate::core::{Color, Point, Vector};
+use crate::utils::clamp;
+
+use std::f64::consts::PI;
+
+// Point light restricted to a cone around the direction it is aimed in, fading
+// out smoothly between `falloff_start` and `cone_angle` from its axis.
+#[derive(Clone)]
+pub struct Spot {
+    position: Point,
+    axis: Vector,
+    intensity: Color,
+    cos_cone: f64,
+    cos_falloff_start: f64,
+    // Relative intensity at evenly spaced angles from the axis, covering 0 to 180
+    // degrees, in the manner of the vertical angles of an IES photometric file.
+    profile: Option<Vec<f64>>,
+}
+
+impl Spot {
+    // Angles are in degrees.
+    pub fn new_light(
+        position: Point,
+        target: Point,
+        intensity: Color,
+        cone_angle: f64,
+        falloff_start: f64,
+    ) -> Light {
+        Light::Spot(Self::new(
+            position,
+            target,
+            intensity,
+            cone_angle,
+            falloff_start,
+            None,
+        ))
+    }
+
+    pub fn new_profiled_light(
+        position: Point,
+        target: Point,
+        intensity: Color,
+        cone_angle: f64,
+        falloff_start: f64,
+        profile: Vec<f64>,
+    ) -> Light {
+        let profile = if profile.is_empty() {
+            None
+        } else {
+            Some(profile)
+        };
+        Light::Spot(Self::new(
+            position,
+            target,
+            intensity,
+            cone_angle,
+            falloff_start,
+            profile,
+        ))
+    }
+
+    fn new(
+        position: Point,
+        target: Point,
+        intensity: Color,
+        cone_angle: f64,
+        falloff_start: f64,
+        profile: Option<Vec<f64>>,
+    ) -> Self {
+        Self {
+            position,
+            axis: (target - position).unit_vector(),
+            intensity,
+            cos_cone: cone_angle.to_radians().cos(),
+            cos_falloff_start: falloff_start.min(cone_angle).to_radians().cos(),
+            profile,
+        }
+    }
+
+    pub fn illuminate(&self, p: Point) -> Option<(Vector, f64, Color)> {
+        let to_light = self.position - p;
+        let distance_squared = to_light.length_squared();
+        if distance_squared <= 0.0 {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        let direction = to_light / distance;
+        let scale = self.falloff(-direction.dot(self.axis));
+        if scale <= 0.0 {
+            return None;
+        }
+
+        Some((
+            direction,
+            distance,
+            self.intensity * scale / distance_squared,
+        ))
+    }
+
+    fn falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta < self.cos_cone {
+            return 0.0;
+        }
+
+        let cone = if cos_theta >= self.cos_falloff_start {
+            1.0
+        } else {
+            let t = (cos_theta - self.cos_cone) / (self.cos_falloff_start - self.cos_cone);
+            t * t * (3.0 - 2.0 * t)
+        };
+
+        cone * self.profile_value(clamp(cos_theta, -1.0, 1.0).acos())
+    }
+
+    fn profile_value(&self, theta: f64) -> f64 {
+        let profile = match &self.profile {
+            Some(profile) => profile,
+            None => return 1.0,
+        };
+        if profile.len() == 1 {
+            return profile[0];
+        }
+
+        let x = theta / PI * (profile.len() - 1) as f64;
+        let i = (x as usize).min(profile.len() - 2);
+        let t = x - i as f64;
+        profile[i] * (1.0 - t) + profile[i + 1] * t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spot(profile: Vec<f64>) -> Spot {
+        match Spot::new_profiled_light(
+            Point::from(0),
+            Point::new(0, -1, 0),
+            Color::from(1),
+            40.0,
+            20.0,
+            profile,
+        ) {
+            Light::Spot(spot) => spot,
+            _ => unreachable!(),
+        }
+    }
+
+    fn cos_degrees(degrees: f64) -> f64 {
+        degrees.to_radians().cos()
+    }
+
+    #[test]
+    fn falloff_is_smooth_between_the_two_angles() {
+        let spot = spot(Vec::new());
+        assert_eq!(spot.falloff(cos_degrees(0.0)), 1.0);
+        assert_eq!(spot.falloff(cos_degrees(19.0)), 1.0);
+        assert_eq!(spot.falloff(cos_degrees(41.0)), 0.0);
+
+        let halfway = 0.5 * (cos_degrees(20.0) + cos_degrees(40.0));
+        assert!((spot.falloff(halfway) - 0.5).abs() < 1e-12);
+
+        let (inner, outer) = (
+            spot.falloff(cos_degrees(25.0)),
+            spot.falloff(cos_degrees(35.0)),
+        );
+        assert!(1.0 > inner && inner > outer && outer > 0.0);
+    }
+
+    #[test]
+    fn profiles_are_interpolated_over_half_a_turn() {
+        let spot = spot(vec![1.0, 0.5, 0.0]);
+        assert!((spot.profile_value(0.0) - 1.0).abs() < 1e-12);
+        assert!((spot.profile_value(PI / 4.0) - 0.75).abs() < 1e-12);
+        assert!((spot.profile_value(PI / 2.0) - 0.5).abs() < 1e-12);
+        assert!((spot.profile_value(PI) - 0.0).abs() < 1e-12);
+
+        assert_eq!(self::spot(vec![0.3]).profile_value(1.0), 0.3);
+        assert_eq!(self::spot(Vec::new()).profile_value(1.0), 1.0);
+    }
+
+    #[test]
+    fn illumination_combines_falloff_profile_and_distance() {
+        let spot = spot(vec![1.0, 0.0]);
+        let p = Point::new(0, -2, 0);
+        let (direction, distance, irradiance) = spot.illuminate(p).unwrap();
+
+        assert!((direction - Vector::new(0, 1, 0)).length() < 1e-12);
+        assert_eq!(distance, 2.0);
+        assert!((irradiance - Color::from(0.25)).length() < 1e-12);
+
+        // Ten degrees off the axis the profile has dropped by 10/180.
+        let p = Point::new(10f64.to_radians().tan(), -1.0, 0.0);
+        let (_, distance, irradiance) = spot.illuminate(p).unwrap();
+        let expected = (1.0 - 10.0 / 180.0) / (distance * distance);
+        assert!((irradiance.x() - expected).abs() < 1e-9);
+
+        assert!(spot.illuminate(Point::new(0, 2, 0)).is_none());
+    }
+}
@@ -1,27 +1,24 @@
-use rayon::prelude::*;
-
-use rand::prelude::*;
-
-use raytracer::core::{Camera, Color, Point, Vector};
+use raytracer::core::{Camera, Point, Vector};
+use raytracer::integrators::IntegratorKind;
 use raytracer::objects::Bvh;
+use raytracer::render::{Renderer, Settings};
 use raytracer::scene;
-use std::f64::INFINITY;
 
-fn main() {
-    let background = Color::from(0);
+use std::fs::File;
+use std::io::{self, BufWriter};
 
+fn main() -> io::Result<()> {
     let aspect_ratio = 16.0 / 9.0;
     let image_width = 1920;
-    let image_height = (image_width as f64 / aspect_ratio) as u16;
+    let image_height = (image_width as f64 / aspect_ratio) as usize;
     let samples_per_pixel = 50;
-    let max_depth = 20;
-
-    println!("P3\n{} {}\n255", image_width, image_height);
+    let max_depth = 50;
+    let rr_min_depth = 3;
 
     let mut rng = rand::thread_rng();
 
-    let world = scene::scene(&mut rng);
-    let world_bvh = Bvh::new(world.objects, 0.001..INFINITY, &mut rng);
+    let scene = scene::scene(&mut rng);
+    let world_bvh = Bvh::new(scene.world.objects, 0.001..f64::INFINITY, &mut rng);
 
     let look_from = Point::new(478, 278, -600);
     let look_at = Point::new(278, 278, 0);
@@ -41,29 +38,26 @@ fn main() {
         1.0,
     );
 
-    for j in (0..image_height).rev() {
-        eprint!("\rScanlines remaining: {:>4}", j);
-
-        let line = (0..image_width)
-            .into_par_iter()
-            .map(|i| {
-                let mut rng = thread_rng();
-                let mut pixel_color = Color::from(0);
-                for _ in 0..samples_per_pixel {
-                    let u = (i as f64 + rng.gen::<f64>()) / (image_width as f64 - 1.0);
-                    let v = (j as f64 + rng.gen::<f64>()) / (image_height as f64 - 1.0);
-                    let ray = camera.ray(u, v, &mut rng);
-                    pixel_color += ray.color(background, &world_bvh, max_depth, &mut rng);
-                }
-                pixel_color.write_color(samples_per_pixel)
-            })
-            .reduce(String::new, |mut acc, val| {
-                acc.push_str(&val);
-                acc.push(' ');
-                acc
-            });
-
-        println!("{}", line);
+    let settings = Settings {
+        image_width,
+        image_height,
+        samples_per_pixel,
+        integrator: IntegratorKind::Path {
+            max_depth,
+            rr_min_depth,
+            spectral: false,
+        },
+        aovs: vec![],
+        denoiser: None,
+    };
+
+    let renderer = Renderer::new(settings, camera);
+    let frame = renderer.render(&world_bvh, &scene.lights, scene.environment.as_ref());
+
+    for (aov, buffer) in frame.aovs.iter() {
+        let file = File::create(format!("{}.pfm", aov.name()))?;
+        buffer.write_pfm(&mut BufWriter::new(file))?;
     }
-    eprintln!("\nDone!");
+
+    frame.beauty.write_ppm(&mut io::stdout().lock())
 }
@@ -0,0 +1,79 @@
+use super::Material;
+use crate::core::Ray;
+use crate::objects::HitRecord;
+use crate::textures::Texture;
+
+// Offset in texture space used for finite differences of the height field.
+const DELTA: f64 = 1e-3;
+
+// Perturbs the shading normal of `base` as if the surface were displaced
+// along its normal by `scale` times a scalar height texture.
+#[derive(Clone)]
+pub struct Bump {
+    base: Box<Material>,
+    height: Texture,
+    scale: f64,
+}
+
+impl Bump {
+    pub fn new_material(base: Material, height: Texture, scale: f64) -> Material {
+        Material::Bump(Self {
+            base: Box::new(base),
+            height,
+            scale,
+        })
+    }
+
+    pub fn base(&self) -> &Material {
+        &self.base
+    }
+
+    pub fn shade<'m>(&self, ray_in: &Ray, rec: &HitRecord<'m>) -> HitRecord<'m> {
+        let height = |du: f64, dv: f64| {
+            let p = rec.p + rec.dpdu * du + rec.dpdv * dv;
+            self.scale * self.height.scalar(rec.u + du, rec.v + dv, p)
+        };
+
+        let displacement = height(0.0, 0.0);
+        let dpdu = rec.dpdu + rec.normal * ((height(DELTA, 0.0) - displacement) / DELTA);
+        let dpdv = rec.dpdv + rec.normal * ((height(0.0, DELTA) - displacement) / DELTA);
+
+        let normal = dpdu.cross(dpdv);
+        if normal.length_squared() == 0.0 {
+            return *rec;
+        }
+
+        let normal = normal.unit_vector();
+        let normal = if normal.dot(rec.normal) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        rec.with_shading_normal(ray_in, normal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{hit, ray_from};
+    use super::super::Material;
+    use super::Bump;
+    use crate::core::Vector;
+    use crate::textures::SolidColor;
+
+    #[test]
+    fn flat_height_map_leaves_the_normal_unchanged() {
+        let base = Material::Lambertian {
+            albedo: SolidColor::from(0.5),
+        };
+        let material = Bump::new_material(base, SolidColor::from(0.7), 2.0);
+        let bump = match &material {
+            Material::Bump(bump) => bump,
+            _ => unreachable!(),
+        };
+
+        let rec = hit(&material);
+        let shaded = bump.shade(&ray_from(Vector::new(1, 0, 1)), &rec);
+        assert!((shaded.normal - rec.normal).length() < 1e-12);
+    }
+}
@@ -0,0 +1,185 @@
+use super::microfacet::Ggx;
+use super::{fresnel, Material, ScatterRecord};
+use crate::core::{Color, Onb, Ray, Vector};
+use crate::objects::HitRecord;
+use crate::textures::{SolidColor, Texture};
+use crate::utils::clamp;
+
+use std::f64::consts::PI;
+
+use rand::prelude::*;
+
+// A dielectric coat over a diffuse base, after Ashikhmin and Shirley 2000. The
+// base is scaled by the energy the coat lets through in both directions.
+#[derive(Clone)]
+pub struct Coated {
+    albedo: Texture,
+    ref_index: f64,
+    roughness: Texture,
+}
+
+struct LocalFrame {
+    onb: Onb,
+    wo: Vector,
+    albedo: Color,
+    f0: f64,
+    distribution: Ggx,
+}
+
+impl Coated {
+    pub fn new_material(albedo: Texture, ref_index: f64, roughness: f64) -> Material {
+        Self::new_textured_material(albedo, ref_index, SolidColor::from(roughness))
+    }
+
+    pub fn new_textured_material(albedo: Texture, ref_index: f64, roughness: Texture) -> Material {
+        Material::Coated(Self {
+            albedo,
+            ref_index,
+            roughness,
+        })
+    }
+
+    pub fn albedo(&self, rec: &HitRecord) -> Color {
+        self.albedo.value(rec.u, rec.v, rec.p)
+    }
+
+    fn frame(&self, ray_in: &Ray, rec: &HitRecord) -> LocalFrame {
+        let onb = Onb::from_w(rec.normal);
+        let roughness = self.roughness.scalar(rec.u, rec.v, rec.p);
+        let r0 = (self.ref_index - 1.0) / (self.ref_index + 1.0);
+
+        LocalFrame {
+            onb,
+            wo: onb.to_local(-ray_in.direction.unit_vector()),
+            albedo: self.albedo.value(rec.u, rec.v, rec.p),
+            f0: r0 * r0,
+            distribution: Ggx::from_roughness(roughness, roughness),
+        }
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        let frame = self.frame(ray_in, rec);
+        let wo = frame.wo;
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let specular = frame.specular_probability();
+        if rng.gen::<f64>() < specular {
+            let wh = if frame.distribution.is_smooth() {
+                Vector::new(0, 0, 1)
+            } else {
+                frame.distribution.sample_wh(wo, rng)
+            };
+            let wi = (-wo).reflect(wh);
+            let scattered = Ray::new(rec.p, frame.onb.local(wi), ray_in.time);
+
+            if frame.distribution.is_smooth() {
+                let reflectance = fresnel::schlick(wo.z(), Color::from(frame.f0));
+                return Some(ScatterRecord::specular(scattered, reflectance / specular));
+            }
+            return frame.scatter_towards(scattered, wi);
+        }
+
+        let wi = Vector::random_cosine_direction(rng);
+        let scattered = Ray::new(rec.p, frame.onb.local(wi), ray_in.time);
+        frame.scatter_towards(scattered, wi)
+    }
+
+    pub fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> Color {
+        let frame = self.frame(ray_in, rec);
+        frame.eval(frame.onb.to_local(direction.unit_vector()))
+    }
+
+    pub fn pdf(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> f64 {
+        let frame = self.frame(ray_in, rec);
+        frame.pdf(frame.onb.to_local(direction.unit_vector()))
+    }
+}
+
+impl LocalFrame {
+    // Chance of sampling the coat rather than the base, from the Fresnel
+    // reflectance at the outgoing angle.
+    fn specular_probability(&self) -> f64 {
+        let reflectance = fresnel::schlick(self.wo.z(), Color::from(self.f0)).x();
+        clamp(reflectance, 0.1, 0.9)
+    }
+
+    fn scatter_towards(&self, scattered: Ray, wi: Vector) -> Option<ScatterRecord> {
+        let pdf = self.pdf(wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+        Some(ScatterRecord::new(scattered, self.eval(wi), pdf))
+    }
+
+    fn eval(&self, wi: Vector) -> Color {
+        let wo = self.wo;
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Color::from(0);
+        }
+
+        let weight = |cos: f64| 1.0 - (1.0 - cos / 2.0).powi(5);
+        let diffuse = self.albedo
+            * (28.0 / (23.0 * PI))
+            * (1.0 - self.f0)
+            * weight(wi.z())
+            * weight(wo.z())
+            * wi.z();
+
+        // A smooth coat reflects into a delta lobe, which is left to scatter, so
+        // lights can still be sampled against the base.
+        if self.distribution.is_smooth() {
+            return diffuse;
+        }
+
+        let wh = (wo + wi).unit_vector();
+        let reflectance = fresnel::schlick(wo.dot(wh), Color::from(self.f0));
+        let specular =
+            reflectance * self.distribution.d(wh) * self.distribution.g(wo, wi) / (4.0 * wo.z());
+
+        diffuse + specular
+    }
+
+    fn pdf(&self, wi: Vector) -> f64 {
+        let wo = self.wo;
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let specular = self.specular_probability();
+        let diffuse = (1.0 - specular) * wi.z() / PI;
+        if self.distribution.is_smooth() {
+            return diffuse;
+        }
+
+        let wh = (wo + wi).unit_vector();
+        diffuse + specular * self.distribution.pdf(wo, wh) / (4.0 * wo.dot(wh))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::check_bsdf;
+    use super::Coated;
+    use crate::core::Vector;
+    use crate::textures::SolidColor;
+
+    #[test]
+    fn coated_is_sampled_by_its_pdf() {
+        let material = Coated::new_material(SolidColor::new_texture(0.6, 0.3, 0.1), 1.5, 0.4);
+        check_bsdf(&material, Vector::new(1, 0, 1));
+    }
+
+    #[test]
+    fn smooth_coated_base_is_sampled_by_its_pdf() {
+        let material = Coated::new_material(SolidColor::new_texture(0.6, 0.3, 0.1), 1.5, 0.0);
+        check_bsdf(&material, Vector::new(1, 0, 1));
+        check_bsdf(&material, Vector::new(0, 0, 1));
+    }
+}
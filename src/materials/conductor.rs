@@ -0,0 +1,207 @@
+use super::microfacet::Ggx;
+use super::{fresnel, thin_film, Material, ScatterRecord, ThinFilm};
+use crate::core::{Color, Onb, Ray, Vector};
+use crate::objects::HitRecord;
+
+use rand::prelude::*;
+
+#[derive(Clone)]
+pub struct Conductor {
+    eta: Color,
+    k: Color,
+    distribution: Ggx,
+    film: Option<ThinFilm>,
+}
+
+impl Conductor {
+    pub fn new_material(eta: Color, k: Color, roughness: f64) -> Material {
+        Self::new_anisotropic_material(eta, k, roughness, roughness)
+    }
+
+    pub fn new_anisotropic_material(
+        eta: Color,
+        k: Color,
+        roughness_u: f64,
+        roughness_v: f64,
+    ) -> Material {
+        Material::Conductor(Self {
+            eta,
+            k,
+            distribution: Ggx::from_roughness(roughness_u, roughness_v),
+            film: None,
+        })
+    }
+
+    pub fn new_thin_film_material(
+        eta: Color,
+        k: Color,
+        roughness: f64,
+        film: ThinFilm,
+    ) -> Material {
+        Material::Conductor(Self {
+            eta,
+            k,
+            distribution: Ggx::from_roughness(roughness, roughness),
+            film: Some(film),
+        })
+    }
+
+    pub fn gold(roughness: f64) -> Material {
+        Self::new_material(
+            Color::new(0.143, 0.374, 1.442),
+            Color::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper(roughness: f64) -> Material {
+        Self::new_material(
+            Color::new(0.200, 0.924, 1.102),
+            Color::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn aluminium(roughness: f64) -> Material {
+        Self::new_material(
+            Color::new(1.657, 0.880, 0.521),
+            Color::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    pub fn silver(roughness: f64) -> Material {
+        Self::new_material(
+            Color::new(0.155, 0.117, 0.138),
+            Color::new(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+
+    pub fn albedo(&self) -> Color {
+        fresnel::conductor(1.0, self.eta, self.k)
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        self.film.is_some()
+    }
+
+    pub fn has_non_delta(&self) -> bool {
+        !self.distribution.is_smooth()
+    }
+
+    fn fresnel(&self, cos_theta_i: f64, rec: &HitRecord, wavelength: Option<f64>) -> Color {
+        match &self.film {
+            Some(film) => {
+                let substrate = |lambda| {
+                    (
+                        thin_film::rgb_at(self.eta, lambda),
+                        thin_film::rgb_at(self.k, lambda),
+                    )
+                };
+                film.reflectance(rec, cos_theta_i, 1.0, substrate, wavelength)
+            }
+            None => fresnel::conductor(cos_theta_i, self.eta, self.k),
+        }
+    }
+
+    // Anisotropic roughness is measured along the surface's u and v directions.
+    fn frame(rec: &HitRecord) -> Onb {
+        Onb::from_wu(rec.normal, rec.dpdu)
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        let onb = Self::frame(rec);
+        let wo = onb.to_local(-ray_in.direction.unit_vector());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        if self.distribution.is_smooth() {
+            let wi = Vector::new(-wo.x(), -wo.y(), wo.z());
+            let scattered = Ray::new(rec.p, onb.local(wi), ray_in.time);
+            let attenuation = self.fresnel(wo.z(), rec, ray_in.wavelength);
+            return Some(ScatterRecord::specular(scattered, attenuation));
+        }
+
+        let wh = self.distribution.sample_wh(wo, rng);
+        let wi = (-wo).reflect(wh);
+        if wi.z() <= 0.0 {
+            return None;
+        }
+
+        let direction = onb.local(wi);
+        let pdf = self.pdf(ray_in, rec, direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let scattered = Ray::new(rec.p, direction, ray_in.time);
+        Some(ScatterRecord::new(
+            scattered,
+            self.eval(ray_in, rec, direction),
+            pdf,
+        ))
+    }
+
+    pub fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> Color {
+        if self.distribution.is_smooth() {
+            return Color::from(0);
+        }
+
+        let onb = Self::frame(rec);
+        let wo = onb.to_local(-ray_in.direction.unit_vector());
+        let wi = onb.to_local(direction.unit_vector());
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Color::from(0);
+        }
+
+        let wh = (wo + wi).unit_vector();
+        let f = self.fresnel(wi.dot(wh), rec, ray_in.wavelength);
+        f * self.distribution.d(wh) * self.distribution.g(wo, wi) / (4.0 * wo.z())
+    }
+
+    pub fn pdf(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> f64 {
+        if self.distribution.is_smooth() {
+            return 0.0;
+        }
+
+        let onb = Self::frame(rec);
+        let wo = onb.to_local(-ray_in.direction.unit_vector());
+        let wi = onb.to_local(direction.unit_vector());
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let wh = (wo + wi).unit_vector();
+        self.distribution.pdf(wo, wh) / (4.0 * wo.dot(wh))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::check_bsdf;
+    use super::Conductor;
+    use crate::core::{Color, Vector};
+
+    #[test]
+    fn rough_conductor_is_sampled_by_its_pdf() {
+        check_bsdf(&Conductor::gold(0.5), Vector::new(1, 0, 1));
+    }
+
+    #[test]
+    fn anisotropic_conductor_is_sampled_by_its_pdf() {
+        let material = Conductor::new_anisotropic_material(
+            Color::new(0.2, 0.9, 1.1),
+            Color::new(3.9, 2.5, 2.1),
+            0.4,
+            0.8,
+        );
+        check_bsdf(&material, Vector::new(1, 1, 1));
+    }
+}
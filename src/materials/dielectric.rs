@@ -0,0 +1,127 @@
+use super::{Ior, Material, ScatterRecord, ThinFilm};
+use crate::core::{Color, Ray};
+use crate::objects::HitRecord;
+use crate::spectrum;
+use crate::utils::{clamp, schlick};
+
+use rand::prelude::*;
+
+#[derive(Clone)]
+pub struct Dielectric {
+    ior: Ior,
+    absorption: Color,
+    film: Option<ThinFilm>,
+}
+
+impl Dielectric {
+    pub fn new_material(ref_index: f64) -> Material {
+        Self::new_absorbing_material(ref_index, Color::from(0))
+    }
+
+    // `absorption` is the Beer-Lambert coefficient per unit distance travelled inside.
+    pub fn new_absorbing_material(ref_index: f64, absorption: Color) -> Material {
+        Material::Dielectric(Self {
+            ior: Ior::Constant(ref_index),
+            absorption,
+            film: None,
+        })
+    }
+
+    // A bubble is a film on a dielectric with `ref_index` 1.
+    pub fn new_thin_film_material(ref_index: f64, film: ThinFilm) -> Material {
+        Material::Dielectric(Self {
+            ior: Ior::Constant(ref_index),
+            absorption: Color::from(0),
+            film: Some(film),
+        })
+    }
+
+    pub fn new_dispersive_material(ior: Ior) -> Material {
+        Material::Dielectric(Self {
+            ior,
+            absorption: Color::from(0),
+            film: None,
+        })
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        self.ior.is_dispersive() || self.film.is_some()
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        let mut attenuation = self.transmittance(ray_in, rec);
+
+        // The first dispersive hit picks the single wavelength the rest of the path carries.
+        let wavelength = match ray_in.wavelength {
+            None if self.ior.is_dispersive() => {
+                let (wavelength, weight) = spectrum::sample_wavelength(rng);
+                attenuation *= weight;
+                Some(wavelength)
+            }
+            wavelength => wavelength,
+        };
+
+        let ref_index = self.ior.at(wavelength);
+        let etai_over_etat = if rec.front_face {
+            1.0 / ref_index
+        } else {
+            ref_index
+        };
+
+        let unit_direction = ray_in.direction.unit_vector();
+        let cos_theta = (-unit_direction).dot(rec.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        let reflected = unit_direction.reflect(rec.normal);
+        let refracted = unit_direction.refract(rec.normal, etai_over_etat);
+
+        let direction = if etai_over_etat * sin_theta > 1.0 {
+            reflected
+        } else if let Some(film) = &self.film {
+            let (outer, inner) = if rec.front_face {
+                (1.0, ref_index)
+            } else {
+                (ref_index, 1.0)
+            };
+            let reflectance = film.reflectance(rec, cos_theta, outer, |_| (inner, 0.0), wavelength);
+            let channel = |c: usize| clamp(reflectance[c], 0.0, 1.0);
+            let reflectance = Color::new(channel(0), channel(1), channel(2));
+
+            // Pick a side by the mean reflectance and reweight to the full colour.
+            let probability = (reflectance.x() + reflectance.y() + reflectance.z()) / 3.0;
+            if rng.gen::<f64>() < probability {
+                attenuation *= reflectance / probability;
+                reflected
+            } else {
+                attenuation *= (Color::from(1) - reflectance) / (1.0 - probability);
+                refracted
+            }
+        } else if rng.gen::<f64>() < schlick(cos_theta, etai_over_etat) {
+            reflected
+        } else {
+            refracted
+        };
+
+        let scattered = Ray {
+            wavelength,
+            ..Ray::new(rec.p, direction, ray_in.time)
+        };
+        Some(ScatterRecord::specular(scattered, attenuation))
+    }
+
+    // A back face hit means the ray has just travelled through the inside of the object.
+    fn transmittance(&self, ray_in: &Ray, rec: &HitRecord) -> Color {
+        if rec.front_face {
+            return Color::from(1);
+        }
+
+        let distance = rec.t * ray_in.direction.length();
+        let channel = |c: usize| (-self.absorption[c] * distance).exp();
+        Color::new(channel(0), channel(1), channel(2))
+    }
+}
@@ -0,0 +1,141 @@
+use crate::core::{Color, Onb, Vector};
+
+use std::f64::consts::PI;
+
+use rand::prelude::*;
+
+// Qualitative Oren-Nayar model for rough diffuse surfaces, where `sigma` is the
+// standard deviation in radians of the microfacet slope angle. Returns f·cos.
+pub fn oren_nayar(albedo: Color, sigma: f64, normal: Vector, wo: Vector, wi: Vector) -> Color {
+    let cos_o = normal.dot(wo);
+    let cos_i = normal.dot(wi);
+    if cos_o <= 0.0 || cos_i <= 0.0 {
+        return Color::from(0);
+    }
+
+    let sigma2 = sigma * sigma;
+    let a = 1.0 - sigma2 / (2.0 * (sigma2 + 0.33));
+    let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+    // Cosine of the azimuthal angle between the two directions.
+    let tangent_o = wo - normal * cos_o;
+    let tangent_i = wi - normal * cos_i;
+    let lengths = (tangent_o.length_squared() * tangent_i.length_squared()).sqrt();
+    let cos_phi = if lengths > 0.0 {
+        (tangent_o.dot(tangent_i) / lengths).max(0.0)
+    } else {
+        0.0
+    };
+
+    // sin(alpha) * tan(beta), with alpha the larger polar angle and beta the smaller.
+    let sin = |cos: f64| (1.0 - cos * cos).max(0.0).sqrt();
+    let (sin_alpha, tan_beta) = if cos_i > cos_o {
+        (sin(cos_o), sin(cos_i) / cos_i)
+    } else {
+        (sin(cos_i), sin(cos_o) / cos_o)
+    };
+
+    albedo * ((a + b * cos_phi * sin_alpha * tan_beta) * cos_i / PI)
+}
+
+// Velvet-like sheen with the "Charlie" distribution of Estevez and Kulla 2017
+// and Neubelt and Pettineo's visibility term. Returns f·cos.
+pub fn sheen(color: Color, roughness: f64, normal: Vector, wo: Vector, wi: Vector) -> Color {
+    let cos_o = normal.dot(wo);
+    let cos_i = normal.dot(wi);
+    if cos_o <= 0.0 || cos_i <= 0.0 {
+        return Color::from(0);
+    }
+
+    let cos_h = normal.dot((wo + wi).unit_vector());
+    let visibility = 1.0 / (4.0 * (cos_i + cos_o - cos_i * cos_o));
+
+    color * (charlie(roughness, cos_h) * visibility * cos_i)
+}
+
+// Direction reflected about a half vector drawn in proportion to D·cos(theta_h),
+// which the Charlie distribution integrates to one over the hemisphere. Most of
+// the lobe lies near grazing half vectors, so reflections that end up below the
+// surface are mirrored back above it instead of being lost.
+pub fn sample_sheen(roughness: f64, normal: Vector, wo: Vector, rng: &mut ThreadRng) -> Vector {
+    let sin_theta = rng
+        .gen::<f64>()
+        .powf(1.0 / (2.0 + 1.0 / roughness.max(1e-3)));
+    let cos_theta = (1.0 - sin_theta * sin_theta).max(0.0).sqrt();
+    let phi = 2.0 * PI * rng.gen::<f64>();
+
+    let wh = Onb::from_w(normal).local(Vector::new(
+        sin_theta * phi.cos(),
+        sin_theta * phi.sin(),
+        cos_theta,
+    ));
+    let wi = (-wo).reflect(wh);
+    if normal.dot(wi) < 0.0 {
+        mirror(wi, normal)
+    } else {
+        wi
+    }
+}
+
+// Solid angle density of `sample_sheen`.
+pub fn sheen_pdf(roughness: f64, normal: Vector, wo: Vector, wi: Vector) -> f64 {
+    if normal.dot(wo) <= 0.0 || normal.dot(wi) <= 0.0 {
+        return 0.0;
+    }
+
+    reflection_pdf(roughness, normal, wo, wi)
+        + reflection_pdf(roughness, normal, wo, mirror(wi, normal))
+}
+
+// Density of reflecting `wo` about a sampled half vector into `wi`, anywhere on
+// the sphere. Reflecting about h or -h gives the same direction and D only
+// depends on sin(theta_h), so the half vector's sign does not matter.
+fn reflection_pdf(roughness: f64, normal: Vector, wo: Vector, wi: Vector) -> f64 {
+    let wh = wo + wi;
+    if wh.length_squared() == 0.0 {
+        return 0.0;
+    }
+
+    let wh = wh.unit_vector();
+    let cos_h = normal.dot(wh).abs();
+    charlie(roughness, cos_h) * cos_h / (4.0 * wo.dot(wh).abs())
+}
+
+fn mirror(v: Vector, normal: Vector) -> Vector {
+    v - normal * (2.0 * normal.dot(v))
+}
+
+fn charlie(roughness: f64, cos_h: f64) -> f64 {
+    let sin2_h = (1.0 - cos_h * cos_h).max(0.0);
+    let inv_roughness = 1.0 / roughness.max(1e-3);
+    (2.0 + inv_roughness) * sin2_h.powf(inv_roughness / 2.0) / (2.0 * PI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::check_bsdf;
+    use super::super::Material;
+    use crate::core::Vector;
+    use crate::textures::SolidColor;
+
+    #[test]
+    fn oren_nayar_is_sampled_by_its_pdf() {
+        let material = Material::OrenNayar {
+            albedo: SolidColor::from(0.8),
+            sigma: SolidColor::from(0.5),
+        };
+        check_bsdf(&material, Vector::new(1, 0, 1));
+    }
+
+    #[test]
+    fn sheen_is_sampled_by_its_pdf() {
+        for &roughness in &[0.3, 1.0] {
+            let material = Material::Sheen {
+                albedo: SolidColor::from(1),
+                roughness: SolidColor::from(roughness),
+            };
+            check_bsdf(&material, Vector::new(1, 0, 0.5));
+            check_bsdf(&material, Vector::new(0, 0, 1));
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use super::Material;
+use crate::core::Color;
+use crate::objects::HitRecord;
+use crate::spectrum::{Spectrum, Wavelengths};
+use crate::textures::{SolidColor, Texture};
+
+use std::f64::consts::PI;
+
+#[derive(Clone)]
+pub struct DiffuseLight {
+    emit: Texture,
+    spectrum: Option<(Spectrum, f64)>,
+    scale: f64,
+    // One-sided lights only emit from their front face.
+    two_sided: bool,
+}
+
+impl DiffuseLight {
+    pub fn new_material(emit: Texture) -> Material {
+        Self::new_scaled_material(emit, 1.0, true)
+    }
+
+    pub fn new_one_sided_material(emit: Texture) -> Material {
+        Self::new_scaled_material(emit, 1.0, false)
+    }
+
+    pub fn new_scaled_material(emit: Texture, scale: f64, two_sided: bool) -> Material {
+        Material::DiffuseLight(Self {
+            emit,
+            spectrum: None,
+            scale,
+            two_sided,
+        })
+    }
+
+    // Emits `spectrum` scaled so that its brightest RGB channel equals `intensity`.
+    pub fn new_spectral_material(spectrum: Spectrum, intensity: f64, two_sided: bool) -> Material {
+        let rgb = spectrum.to_rgb();
+        let scale = intensity / rgb.max_component();
+
+        Material::DiffuseLight(Self {
+            emit: SolidColor::from_color(rgb * scale),
+            spectrum: Some((spectrum, scale)),
+            scale: 1.0,
+            two_sided,
+        })
+    }
+
+    // Colour temperature in kelvin, e.g. 2700 for a warm tungsten bulb.
+    pub fn new_blackbody_material(temperature: f64, intensity: f64, two_sided: bool) -> Material {
+        Self::new_spectral_material(Spectrum::Blackbody { temperature }, intensity, two_sided)
+    }
+
+    // Radiance of an emitter of `area` giving off `watts` in total, spread over
+    // one or both sides. `color` tints the emission and is not normalised.
+    pub fn new_power_material(color: Color, watts: f64, area: f64, two_sided: bool) -> Material {
+        let sides = if two_sided { 2.0 } else { 1.0 };
+        let scale = watts / (sides * PI * area);
+        Self::new_scaled_material(SolidColor::from_color(color), scale, two_sided)
+    }
+
+    // Emitted radiance regardless of which side was hit.
+    pub fn radiance(&self, rec: &HitRecord) -> Color {
+        self.emit.value(rec.u, rec.v, rec.p) * self.scale
+    }
+
+    pub fn emitted(&self, rec: &HitRecord) -> Color {
+        if !self.emits(rec) {
+            return Color::from(0);
+        }
+        self.radiance(rec)
+    }
+
+    pub fn emitted_spectrum(&self, rec: &HitRecord, wavelengths: &Wavelengths) -> Color {
+        if !self.emits(rec) {
+            return Color::from(0);
+        }
+
+        match &self.spectrum {
+            Some((spectrum, scale)) => {
+                wavelengths.map(|lambda| spectrum.value(lambda) * scale * self.scale)
+            }
+            None => wavelengths.uplift(self.radiance(rec)),
+        }
+    }
+
+    fn emits(&self, rec: &HitRecord) -> bool {
+        self.two_sided || rec.front_face
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{hit, ray_from};
+    use super::DiffuseLight;
+    use crate::core::{Color, Point, Ray, Vector};
+    use crate::objects::{FlipFace, HitRecord, Object, XZRect};
+    use crate::spectrum::Wavelengths;
+    use crate::textures::SolidColor;
+
+    use std::f64::consts::PI;
+
+    #[test]
+    fn flipped_one_sided_ceiling_lights_only_shine_down() {
+        let light = DiffuseLight::new_one_sided_material(SolidColor::from(3));
+        let ceiling = FlipFace::new(XZRect::new(-1.0, 1.0, -1.0, 1.0, 2.0, light));
+        let mut rng = rand::thread_rng();
+
+        let mut emitted = |origin: Point, direction: Vector| {
+            let ray = Ray::new(origin, direction, 0.0);
+            let rec = ceiling.hit(&ray, 0.001..f64::INFINITY, &mut rng).unwrap();
+            rec.material.emitted(&ray, &rec)
+        };
+        let below = emitted(Point::new(0, 0, 0), Vector::new(0, 1, 0));
+        let above = emitted(Point::new(0, 4, 0), Vector::new(0, -1, 0));
+
+        assert!((below - Color::from(3)).length() < 1e-12);
+        assert_eq!(above.length(), 0.0);
+    }
+
+    #[test]
+    fn blackbody_lights_can_be_one_sided() {
+        let ray = ray_from(Vector::new(0, 0, 1));
+        let mut rng = rand::thread_rng();
+        let wavelengths = Wavelengths::sample(&mut rng);
+
+        for &two_sided in &[false, true] {
+            let light = DiffuseLight::new_blackbody_material(2700.0, 5.0, two_sided);
+            let front = hit(&light);
+            let back = HitRecord {
+                front_face: false,
+                ..front
+            };
+
+            assert!(light.emitted(&ray, &front).max_component() > 0.0);
+            assert!(
+                light
+                    .emitted_spectrum(&ray, &front, &wavelengths)
+                    .max_component()
+                    > 0.0
+            );
+            assert_eq!(light.emitted(&ray, &back).max_component() > 0.0, two_sided);
+            assert_eq!(
+                light
+                    .emitted_spectrum(&ray, &back, &wavelengths)
+                    .max_component()
+                    > 0.0,
+                two_sided
+            );
+        }
+    }
+
+    #[test]
+    fn power_is_spread_over_the_area_and_sides() {
+        let ray = ray_from(Vector::new(0, 0, 1));
+        let (watts, area) = (100.0, 2.0);
+
+        // A Lambertian emitter of radiance L gives off π L per unit area per side.
+        for &(two_sided, sides) in &[(false, 1.0), (true, 2.0)] {
+            let light = DiffuseLight::new_power_material(Color::from(1), watts, area, two_sided);
+            let radiance = light.emitted(&ray, &hit(&light));
+            let power = radiance * (sides * PI * area);
+            assert!((power - Color::from(watts)).length() < 1e-9, "{:?}", power);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::core::Color;
+use crate::utils::clamp;
+
+pub fn conductor(cos_theta_i: f64, eta: Color, k: Color) -> Color {
+    let channel = |c: usize| conductor_channel(cos_theta_i, eta[c], k[c]);
+    Color::new(channel(0), channel(1), channel(2))
+}
+
+fn conductor_channel(cos_theta_i: f64, eta: f64, k: f64) -> f64 {
+    let cos_theta_i = clamp(cos_theta_i, 0.0, 1.0);
+    let cos2 = cos_theta_i * cos_theta_i;
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+    let t1 = a2_plus_b2 + cos2;
+    let a = (0.5 * (a2_plus_b2 + t0)).max(0.0).sqrt();
+    let t2 = 2.0 * cos_theta_i * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+// `eta` is the ratio of the transmitted to the incident index of refraction.
+pub fn dielectric(cos_theta_i: f64, eta: f64) -> f64 {
+    let cos_theta_i = clamp(cos_theta_i, 0.0, 1.0);
+    let sin2_theta_t = (1.0 - cos_theta_i * cos_theta_i) / (eta * eta);
+    if sin2_theta_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_theta_t = (1.0 - sin2_theta_t).sqrt();
+
+    let r_parallel = (eta * cos_theta_i - cos_theta_t) / (eta * cos_theta_i + cos_theta_t);
+    let r_perpendicular = (cos_theta_i - eta * cos_theta_t) / (cos_theta_i + eta * cos_theta_t);
+    (r_parallel * r_parallel + r_perpendicular * r_perpendicular) / 2.0
+}
+
+pub fn schlick(cos_theta_i: f64, f0: Color) -> Color {
+    let weight = (1.0 - clamp(cos_theta_i, 0.0, 1.0)).powi(5);
+    f0 + (Color::from(1) - f0) * weight
+}
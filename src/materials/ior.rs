@@ -0,0 +1,90 @@
+// Index of refraction, optionally varying with wavelength (given in nanometres).
+#[derive(Copy, Clone, Debug)]
+pub enum Ior {
+    Constant(f64),
+    // n = a + b / λ², with λ in micrometres.
+    Cauchy { a: f64, b: f64 },
+    // n² = 1 + Σ bᵢλ² / (λ² - cᵢ), with λ in micrometres.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+const NOMINAL_WAVELENGTH: f64 = 587.6;
+
+impl Ior {
+    pub fn bk7() -> Self {
+        Ior::Sellmeier {
+            b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        }
+    }
+
+    pub fn diamond() -> Self {
+        Ior::Sellmeier {
+            b: [4.3356, 0.3306, 0.0],
+            c: [0.011_236, 0.030_625, 0.0],
+        }
+    }
+
+    // Schott SF10, fitted to its catalogue n_d and dispersion.
+    pub fn flint() -> Self {
+        Ior::Cauchy {
+            a: 1.6894,
+            b: 0.01342,
+        }
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, Ior::Constant(_))
+    }
+
+    pub fn at(&self, wavelength: Option<f64>) -> f64 {
+        let micrometres = wavelength.unwrap_or(NOMINAL_WAVELENGTH) / 1000.0;
+        let l2 = micrometres * micrometres;
+
+        match *self {
+            Ior::Constant(n) => n,
+            Ior::Cauchy { a, b } => a + b / l2,
+            Ior::Sellmeier { b, c } => (1.0
+                + b.iter()
+                    .zip(c.iter())
+                    .map(|(b, c)| b * l2 / (l2 - c))
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ior;
+
+    // Index at the helium d line, which glass catalogues list.
+    fn n_d(ior: Ior) -> f64 {
+        ior.at(None)
+    }
+
+    #[test]
+    fn presets_match_their_catalogue_index() {
+        for &(ior, expected) in [
+            (Ior::bk7(), 1.5168),
+            (Ior::diamond(), 2.4175),
+            (Ior::flint(), 1.7283),
+        ]
+        .iter()
+        {
+            let n = n_d(ior);
+            assert!((n - expected).abs() < 1e-3, "{} != {}", n, expected);
+        }
+    }
+
+    #[test]
+    fn presets_disperse_blue_more_than_red() {
+        for &ior in [Ior::bk7(), Ior::diamond(), Ior::flint()].iter() {
+            assert!(ior.is_dispersive());
+            assert!(ior.at(Some(450.0)) > ior.at(None));
+            assert!(ior.at(None) > ior.at(Some(650.0)));
+        }
+        assert!(!Ior::Constant(1.5).is_dispersive());
+        assert_eq!(Ior::Constant(1.5).at(Some(450.0)), 1.5);
+    }
+}
@@ -0,0 +1,273 @@
+use super::{Material, ScatterRecord};
+use crate::core::{Color, Onb, Ray, Vector};
+use crate::objects::HitRecord;
+use crate::utils::clamp;
+
+use std::convert::TryInto;
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::sync::Arc;
+
+use rand::prelude::*;
+
+const THETA_H: usize = 90;
+const THETA_D: usize = 90;
+const PHI_D: usize = 180;
+const SAMPLES: usize = THETA_H * THETA_D * PHI_D;
+
+// Channel scale factors used by the MERL database.
+const SCALE: [f64; 3] = [1.0 / 1500.0, 1.15 / 1500.0, 1.66 / 1500.0];
+
+// Chance of drawing a cosine-weighted direction instead of one from the table.
+const COSINE_FALLBACK: f64 = 0.5;
+
+// Isotropic BRDF measured by Matusik et al. 2003, stored in the half/difference
+// angle parameterization of Rusinkiewicz 1998.
+#[derive(Clone)]
+pub struct Merl {
+    // Red, green and blue blocks of SAMPLES values each.
+    data: Arc<Vec<f64>>,
+    // Cumulative distribution over theta_h bins for sampling half vectors.
+    cdf: Arc<Vec<f64>>,
+}
+
+impl Merl {
+    pub fn new_material(filename: &str) -> io::Result<Material> {
+        let bytes = fs::read(filename)?;
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, message.to_string());
+
+        if bytes.len() < 12 {
+            return Err(invalid("truncated MERL header"));
+        }
+        let dimension =
+            |i: usize| i32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()) as usize;
+        if (dimension(0), dimension(1), dimension(2)) != (THETA_H, THETA_D, PHI_D) {
+            return Err(invalid("unexpected MERL dimensions"));
+        }
+        if bytes.len() != 12 + 3 * SAMPLES * 8 {
+            return Err(invalid("unexpected MERL file size"));
+        }
+
+        let data: Vec<f64> = bytes[12..]
+            .chunks_exact(8)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let value = f64::from_le_bytes(chunk.try_into().unwrap());
+                // Negative entries mark samples that were not measured.
+                value.max(0.0) * SCALE[i / SAMPLES]
+            })
+            .collect();
+
+        let cdf = Self::half_vector_cdf(&data);
+        Ok(Material::Merl(Self {
+            data: Arc::new(data),
+            cdf: Arc::new(cdf),
+        }))
+    }
+
+    // Weights each theta_h bin by its average reflectance and solid angle.
+    fn half_vector_cdf(data: &[f64]) -> Vec<f64> {
+        let mut cdf = Vec::with_capacity(THETA_H);
+        let mut total = 0.0;
+
+        let size = THETA_D * PHI_D;
+        for bin in 0..THETA_H {
+            let mean = (0..3)
+                .map(|c| {
+                    let start = c * SAMPLES + bin * size;
+                    data[start..start + size].iter().sum::<f64>()
+                })
+                .sum::<f64>()
+                / (3 * size) as f64;
+
+            let (cos_min, cos_max) = bin_cosines(bin);
+            total += mean * 2.0 * PI * (cos_max - cos_min) + 1e-12;
+            cdf.push(total);
+        }
+
+        cdf.iter().map(|c| c / total).collect()
+    }
+
+    pub fn albedo(&self) -> Color {
+        self.brdf(Vector::new(0, 0, 1), Vector::new(0, 0, 1)) * PI
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        let onb = Onb::from_w(rec.normal);
+        let wo = onb.to_local(-ray_in.direction.unit_vector());
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let wi = if rng.gen::<f64>() < COSINE_FALLBACK {
+            Vector::random_cosine_direction(rng)
+        } else {
+            (-wo).reflect(self.sample_wh(rng))
+        };
+
+        let pdf = self.pdf_local(wo, wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let scattered = Ray::new(rec.p, onb.local(wi), ray_in.time);
+        Some(ScatterRecord::new(scattered, self.eval_local(wo, wi), pdf))
+    }
+
+    pub fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> Color {
+        let onb = Onb::from_w(rec.normal);
+        self.eval_local(
+            onb.to_local(-ray_in.direction.unit_vector()),
+            onb.to_local(direction.unit_vector()),
+        )
+    }
+
+    pub fn pdf(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> f64 {
+        let onb = Onb::from_w(rec.normal);
+        self.pdf_local(
+            onb.to_local(-ray_in.direction.unit_vector()),
+            onb.to_local(direction.unit_vector()),
+        )
+    }
+
+    fn eval_local(&self, wo: Vector, wi: Vector) -> Color {
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return Color::from(0);
+        }
+        self.brdf(wo, wi) * wi.z()
+    }
+
+    fn pdf_local(&self, wo: Vector, wi: Vector) -> f64 {
+        if wo.z() <= 0.0 || wi.z() <= 0.0 {
+            return 0.0;
+        }
+
+        let wh = (wo + wi).unit_vector();
+        let cosine = wi.z() / PI;
+        let table = self.pdf_wh(wh) / (4.0 * wo.dot(wh));
+        COSINE_FALLBACK * cosine + (1.0 - COSINE_FALLBACK) * table
+    }
+
+    fn sample_wh(&self, rng: &mut ThreadRng) -> Vector {
+        let u = rng.gen::<f64>();
+        let bin = self.cdf.partition_point(|&c| c < u).min(THETA_H - 1);
+
+        // Uniform in solid angle within the bin.
+        let (cos_min, cos_max) = bin_cosines(bin);
+        let cos_theta = cos_min + (cos_max - cos_min) * rng.gen::<f64>();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        Vector::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+    }
+
+    fn pdf_wh(&self, wh: Vector) -> f64 {
+        let bin = theta_h_index(wh.z().min(1.0).acos());
+        let mass = self.cdf[bin] - if bin == 0 { 0.0 } else { self.cdf[bin - 1] };
+        let (cos_min, cos_max) = bin_cosines(bin);
+        mass / (2.0 * PI * (cos_max - cos_min))
+    }
+
+    fn brdf(&self, wo: Vector, wi: Vector) -> Color {
+        let wh = (wo + wi).unit_vector();
+        let theta_h = wh.z().min(1.0).acos();
+        let phi_h = wh.y().atan2(wh.x());
+
+        // Express wi in the frame where the half vector is the pole.
+        let diff = rotate_y(rotate_z(wi, -phi_h), -theta_h);
+        let theta_d = clamp(diff.z(), -1.0, 1.0).acos();
+        let mut phi_d = diff.y().atan2(diff.x());
+        // Reciprocity lets phi_d be folded onto [0, pi).
+        if phi_d < 0.0 {
+            phi_d += PI;
+        }
+
+        let theta_d_index = ((theta_d / FRAC_PI_2 * THETA_D as f64) as usize).min(THETA_D - 1);
+        let phi_d_index = ((phi_d / PI * PHI_D as f64) as usize).min(PHI_D - 1);
+        let index = phi_d_index + PHI_D * (theta_d_index + THETA_D * theta_h_index(theta_h));
+
+        Color::new(
+            self.data[index],
+            self.data[index + SAMPLES],
+            self.data[index + 2 * SAMPLES],
+        )
+    }
+}
+
+// Theta_h bins are spaced more densely near the specular peak.
+fn theta_h_index(theta_h: f64) -> usize {
+    if theta_h <= 0.0 {
+        return 0;
+    }
+    (((theta_h / FRAC_PI_2).sqrt() * THETA_H as f64) as usize).min(THETA_H - 1)
+}
+
+// Cosines of the upper and lower theta_h edges of a bin.
+fn bin_cosines(bin: usize) -> (f64, f64) {
+    let theta = |i: usize| {
+        let t = i as f64 / THETA_H as f64;
+        t * t * FRAC_PI_2
+    };
+    (theta(bin + 1).cos(), theta(bin).cos())
+}
+
+fn rotate_z(v: Vector, angle: f64) -> Vector {
+    let (sin, cos) = angle.sin_cos();
+    Vector::new(v.x() * cos - v.y() * sin, v.x() * sin + v.y() * cos, v.z())
+}
+
+fn rotate_y(v: Vector, angle: f64) -> Vector {
+    let (sin, cos) = angle.sin_cos();
+    Vector::new(v.x() * cos + v.z() * sin, v.y(), -v.x() * sin + v.z() * cos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::check_bsdf;
+    use super::*;
+
+    use std::env;
+
+    // Writes a glossy synthetic table in the MERL file format.
+    fn write_table() -> String {
+        let mut bytes = Vec::with_capacity(12 + 3 * SAMPLES * 8);
+        for &dimension in &[THETA_H, THETA_D, PHI_D] {
+            bytes.extend_from_slice(&(dimension as i32).to_le_bytes());
+        }
+        for scale in SCALE.iter() {
+            for i in 0..SAMPLES {
+                let bin = i / (THETA_D * PHI_D);
+                let brdf = 0.2 / PI * (1.0 + 20.0 * (-(bin as f64) / 4.0).exp());
+                bytes.extend_from_slice(&(brdf / scale).to_le_bytes());
+            }
+        }
+
+        let path = env::temp_dir().join(format!("merl-test-{}.binary", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn merl_is_sampled_by_its_pdf() {
+        let path = write_table();
+        let material = Merl::new_material(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        check_bsdf(&material, Vector::new(1, 0, 1));
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        let path = env::temp_dir().join(format!("merl-truncated-{}.binary", std::process::id()));
+        fs::write(&path, [0u8; 8]).unwrap();
+        let result = Merl::new_material(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::core::Vector;
+use crate::utils::clamp;
+
+use std::f64::consts::PI;
+
+use rand::prelude::*;
+
+// Trowbridge-Reitz (GGX) distribution with Smith masking, evaluated in a local
+// shading frame where the normal is +z.
+#[derive(Copy, Clone, Debug)]
+pub struct Ggx {
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl Ggx {
+    pub fn new(alpha_x: f64, alpha_y: f64) -> Self {
+        Self {
+            alpha_x: alpha_x.max(1e-4),
+            alpha_y: alpha_y.max(1e-4),
+        }
+    }
+
+    pub fn from_roughness(roughness_x: f64, roughness_y: f64) -> Self {
+        Self::new(roughness_x * roughness_x, roughness_y * roughness_y)
+    }
+
+    pub fn is_smooth(&self) -> bool {
+        self.alpha_x.max(self.alpha_y) < 1e-3
+    }
+
+    pub fn d(&self, wh: Vector) -> f64 {
+        let x = wh.x() / self.alpha_x;
+        let y = wh.y() / self.alpha_y;
+        let denominator = x * x + y * y + wh.z() * wh.z();
+        1.0 / (PI * self.alpha_x * self.alpha_y * denominator * denominator)
+    }
+
+    pub fn lambda(&self, w: Vector) -> f64 {
+        let z2 = w.z() * w.z();
+        if z2 == 0.0 {
+            return f64::INFINITY;
+        }
+        let x = w.x() * self.alpha_x;
+        let y = w.y() * self.alpha_y;
+        ((1.0 + (x * x + y * y) / z2).sqrt() - 1.0) / 2.0
+    }
+
+    pub fn g1(&self, w: Vector) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    pub fn g(&self, wo: Vector, wi: Vector) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    // Density of sampling `wh` as a visible normal seen from `wo`.
+    pub fn pdf(&self, wo: Vector, wh: Vector) -> f64 {
+        if wo.z() == 0.0 {
+            return 0.0;
+        }
+        self.g1(wo) * wo.dot(wh).abs() * self.d(wh) / wo.z().abs()
+    }
+
+    // Visible normal sampling (Heitz 2018).
+    pub fn sample_wh(&self, wo: Vector, rng: &mut ThreadRng) -> Vector {
+        let flip = wo.z() < 0.0;
+        let wo = if flip { -wo } else { wo };
+
+        let vh = Vector::new(self.alpha_x * wo.x(), self.alpha_y * wo.y(), wo.z()).unit_vector();
+
+        let length_squared = vh.x() * vh.x() + vh.y() * vh.y();
+        let t1 = if length_squared > 0.0 {
+            Vector::new(-vh.y(), vh.x(), 0.0) / length_squared.sqrt()
+        } else {
+            Vector::new(1, 0, 0)
+        };
+        let t2 = vh.cross(t1);
+
+        let r = rng.gen::<f64>().sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z());
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+
+        let nh = t1 * p1 + t2 * p2 + vh * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+        let wh = Vector::new(
+            self.alpha_x * nh.x(),
+            self.alpha_y * nh.y(),
+            nh.z().max(1e-6),
+        )
+        .unit_vector();
+
+        if flip {
+            -wh
+        } else {
+            wh
+        }
+    }
+}
+
+// Generalized Trowbridge-Reitz with γ = 1, used for clearcoat lobes.
+#[derive(Copy, Clone, Debug)]
+pub struct Gtr1 {
+    alpha: f64,
+}
+
+impl Gtr1 {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: clamp(alpha, 1e-3, 0.999),
+        }
+    }
+
+    pub fn d(&self, wh: Vector) -> f64 {
+        let a2 = self.alpha * self.alpha;
+        let t = 1.0 + (a2 - 1.0) * wh.z() * wh.z();
+        (a2 - 1.0) / (PI * a2.ln() * t)
+    }
+
+    pub fn pdf(&self, wh: Vector) -> f64 {
+        self.d(wh) * wh.z().abs()
+    }
+
+    pub fn sample_wh(&self, rng: &mut ThreadRng) -> Vector {
+        let a2 = self.alpha * self.alpha;
+        let cos_theta = ((1.0 - a2.powf(1.0 - rng.gen::<f64>())) / (1.0 - a2)).sqrt();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        Vector::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+    }
+}
@@ -0,0 +1,175 @@
+use super::{Material, ScatterRecord};
+use crate::core::{Color, Point, Ray, Vector};
+use crate::objects::HitRecord;
+use crate::spectrum::Wavelengths;
+use crate::textures::Texture;
+use crate::utils::clamp;
+
+use std::ops::{Add, Mul};
+
+use rand::prelude::*;
+
+// Blends two materials by a scalar mask: 0 is entirely `first`, 1 entirely `second`.
+#[derive(Clone)]
+pub struct Mix {
+    first: Box<Material>,
+    second: Box<Material>,
+    amount: Texture,
+}
+
+impl Mix {
+    pub fn new_material(first: Material, second: Material, amount: Texture) -> Material {
+        Material::Mix(Self {
+            first: Box::new(first),
+            second: Box::new(second),
+            amount,
+        })
+    }
+
+    fn amount(&self, u: f64, v: f64, p: Point) -> f64 {
+        clamp(self.amount.scalar(u, v, p), 0.0, 1.0)
+    }
+
+    fn lerp<T>(&self, u: f64, v: f64, p: Point, first: T, second: T) -> T
+    where
+        T: Mul<f64, Output = T> + Add<Output = T>,
+    {
+        let amount = self.amount(u, v, p);
+        first * (1.0 - amount) + second * amount
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        let chosen = if rng.gen::<f64>() < self.amount(rec.u, rec.v, rec.p) {
+            &self.second
+        } else {
+            &self.first
+        };
+
+        let mut srec = chosen.scatter(ray_in, rec, rng)?;
+        if srec.specular {
+            return Some(srec);
+        }
+
+        // Smooth lobes are picked with the mask probability, so their weight
+        // needs no correction; rough ones use the density of the whole blend.
+        // eval and pdf leave smooth lobes out, so lights are still sampled
+        // against the other material when one is picked.
+        let direction = srec.ray.direction;
+        srec.bsdf = self.eval(ray_in, rec, direction);
+        srec.pdf = self.pdf(ray_in, rec, direction);
+        if srec.pdf <= 0.0 {
+            return None;
+        }
+        Some(srec)
+    }
+
+    pub fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> Color {
+        self.lerp(
+            rec.u,
+            rec.v,
+            rec.p,
+            self.first.eval(ray_in, rec, direction),
+            self.second.eval(ray_in, rec, direction),
+        )
+    }
+
+    pub fn pdf(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> f64 {
+        self.lerp(
+            rec.u,
+            rec.v,
+            rec.p,
+            self.first.pdf(ray_in, rec, direction),
+            self.second.pdf(ray_in, rec, direction),
+        )
+    }
+
+    pub fn albedo(&self, rec: &HitRecord) -> Color {
+        self.lerp(
+            rec.u,
+            rec.v,
+            rec.p,
+            self.first.albedo(rec),
+            self.second.albedo(rec),
+        )
+    }
+
+    pub fn emitted(&self, ray_in: &Ray, rec: &HitRecord) -> Color {
+        self.lerp(
+            rec.u,
+            rec.v,
+            rec.p,
+            self.first.emitted(ray_in, rec),
+            self.second.emitted(ray_in, rec),
+        )
+    }
+
+    pub fn emitted_spectrum(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        wavelengths: &Wavelengths,
+    ) -> Color {
+        self.lerp(
+            rec.u,
+            rec.v,
+            rec.p,
+            self.first.emitted_spectrum(ray_in, rec, wavelengths),
+            self.second.emitted_spectrum(ray_in, rec, wavelengths),
+        )
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        self.first.is_dispersive() || self.second.is_dispersive()
+    }
+
+    pub fn has_non_delta(&self, rec: &HitRecord) -> bool {
+        let amount = self.amount(rec.u, rec.v, rec.p);
+        (amount < 1.0 && self.first.has_non_delta(rec))
+            || (amount > 0.0 && self.second.has_non_delta(rec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{check_bsdf, hit, ray_from};
+    use super::super::{Conductor, Material};
+    use super::Mix;
+    use crate::core::{Color, Vector};
+    use crate::textures::SolidColor;
+
+    #[test]
+    fn mix_is_sampled_by_its_pdf() {
+        let diffuse = Material::Lambertian {
+            albedo: SolidColor::new_texture(0.2, 0.6, 0.3),
+        };
+        let material = Mix::new_material(diffuse, Conductor::gold(0.4), SolidColor::from(0.3));
+        check_bsdf(&material, Vector::new(1, 0, 1));
+    }
+
+    #[test]
+    fn mix_with_a_mirror_leaves_the_mirror_to_scatter() {
+        let mirror = || Material::Metal {
+            albedo: Color::from(1),
+            fuzz: 0.0,
+        };
+        let diffuse = Material::Lambertian {
+            albedo: SolidColor::new_texture(0.2, 0.6, 0.3),
+        };
+        let material = Mix::new_material(diffuse.clone(), mirror(), SolidColor::from(0.5));
+        check_bsdf(&material, Vector::new(1, 0, 1));
+
+        let rec = hit(&material);
+        let ray_in = ray_from(Vector::new(1, 0, 1));
+        let direction = Vector::new(0, 1, 1);
+        let expected = diffuse.eval(&ray_in, &rec, direction) * 0.5;
+        assert!((material.eval(&ray_in, &rec, direction) - expected).length() < 1e-12);
+
+        let only_mirror = Mix::new_material(diffuse, mirror(), SolidColor::from(1));
+        assert!(!only_mirror.has_non_delta(&hit(&only_mirror)));
+    }
+}
@@ -1,17 +1,66 @@
-use crate::core::{Color, Point, Ray, Vector};
+mod bump;
+mod coated;
+mod conductor;
+mod dielectric;
+mod diffuse;
+mod diffuse_light;
+mod fresnel;
+mod ior;
+mod merl;
+mod microfacet;
+mod mix;
+mod normal_map;
+mod principled;
+mod rough_dielectric;
+mod scatter_record;
+mod subsurface;
+#[cfg(test)]
+mod testing;
+mod thin_film;
+
+use crate::core::{Color, Onb, Ray, Vector};
 use crate::objects::HitRecord;
+use crate::spectrum::Wavelengths;
 use crate::textures::Texture;
-use crate::utils::schlick;
+
+pub use bump::Bump;
+pub use coated::Coated;
+pub use conductor::Conductor;
+pub use dielectric::Dielectric;
+pub use diffuse_light::DiffuseLight;
+pub use ior::Ior;
+pub use merl::Merl;
+pub use mix::Mix;
+pub use normal_map::NormalMap;
+pub use principled::Principled;
+pub use rough_dielectric::RoughDielectric;
+pub use scatter_record::ScatterRecord;
+pub use subsurface::Subsurface;
+pub use thin_film::ThinFilm;
+
+use std::f64::consts::PI;
 
 use rand::prelude::*;
 
 #[derive(Clone)]
 pub enum Material {
-    Dielectric { ref_index: f64 },
-    DiffuseLight { emit: Texture },
+    Bump(Bump),
+    Coated(Coated),
+    Conductor(Conductor),
+    Dielectric(Dielectric),
+    DiffuseLight(DiffuseLight),
     Isotropic { albedo: Texture },
     Lambertian { albedo: Texture },
+    Merl(Merl),
     Metal { albedo: Color, fuzz: f64 },
+    Mix(Mix),
+    NormalMap(NormalMap),
+    // `sigma` is the roughness in radians.
+    OrenNayar { albedo: Texture, sigma: Texture },
+    Principled(Principled),
+    RoughDielectric(RoughDielectric),
+    Sheen { albedo: Texture, roughness: Texture },
+    Subsurface(Subsurface),
 }
 
 impl Material {
@@ -20,48 +69,19 @@ impl Material {
         ray_in: &Ray,
         rec: &HitRecord,
         rng: &mut ThreadRng,
-    ) -> Option<(Color, Ray)> {
+    ) -> Option<ScatterRecord> {
         match self {
-            Material::Dielectric { ref_index } => {
-                let attenuation = Color::from(1);
-
-                let etai_over_etat = if rec.front_face {
-                    1.0 / ref_index
-                } else {
-                    *ref_index
-                };
-
-                let unit_direction = ray_in.direction.unit_vector();
-                let cos_theta = (-unit_direction).dot(rec.normal).min(1.0);
-                let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
-                if etai_over_etat * sin_theta > 1.0 {
-                    let reflected = unit_direction.reflect(rec.normal);
-                    let scattered = Ray::new(rec.p, reflected, ray_in.time);
-                    return Some((attenuation, scattered));
-                }
-
-                let reflect_prob = schlick(cos_theta, etai_over_etat);
-                if rng.gen::<f64>() < reflect_prob {
-                    let reflected = unit_direction.reflect(rec.normal);
-                    let scattered = Ray::new(rec.p, reflected, ray_in.time);
-                    return Some((attenuation, scattered));
-                }
-
-                let refracted = unit_direction.refract(rec.normal, etai_over_etat);
-                let scattered = Ray::new(rec.p, refracted, ray_in.time);
-                Some((attenuation, scattered))
+            Material::Bump(b) => b.base().scatter(ray_in, &b.shade(ray_in, rec), rng),
+            Material::Coated(c) => c.scatter(ray_in, rec, rng),
+            Material::Conductor(c) => c.scatter(ray_in, rec, rng),
+            Material::Dielectric(d) => d.scatter(ray_in, rec, rng),
+            Material::DiffuseLight(_) => None,
+            Material::Isotropic { .. } => {
+                self.scatter_towards(ray_in, rec, Vector::random_unit_vector(rng))
             }
-            Material::DiffuseLight { .. } => None,
-            Material::Isotropic { albedo } => {
-                let scattered = Ray::new(rec.p, Vector::random_in_unit_sphere(rng), ray_in.time);
-                let attenuation = albedo.value(rec.u, rec.v, rec.p);
-                Some((attenuation, scattered))
-            }
-            Material::Lambertian { albedo } => {
-                let scatter_direction = rec.normal + Vector::random_unit_vector(rng);
-                let scattered = Ray::new(rec.p, scatter_direction, ray_in.time);
-                let attenuation = albedo.value(rec.u, rec.v, rec.p);
-                Some((attenuation, scattered))
+            Material::Lambertian { .. } | Material::OrenNayar { .. } => {
+                let direction = Onb::from_w(rec.normal).local(Vector::random_cosine_direction(rng));
+                self.scatter_towards(ray_in, rec, direction)
             }
             Material::Metal { albedo, fuzz } => {
                 let fuzz = fuzz.min(1.0);
@@ -73,18 +93,227 @@ impl Material {
                 );
 
                 if scattered.direction.dot(rec.normal) > 0.0 {
-                    Some((*albedo, scattered))
+                    Some(ScatterRecord::specular(scattered, *albedo))
                 } else {
                     None
                 }
             }
+            Material::Merl(m) => m.scatter(ray_in, rec, rng),
+            Material::Mix(m) => m.scatter(ray_in, rec, rng),
+            Material::NormalMap(n) => n.base().scatter(ray_in, &n.shade(ray_in, rec), rng),
+            Material::Principled(p) => p.scatter(ray_in, rec, rng),
+            Material::RoughDielectric(d) => d.scatter(ray_in, rec, rng),
+            // Half the directions follow the sheen lobe and half the cosine, so
+            // both grazing and head-on light are found.
+            Material::Sheen { roughness, .. } => {
+                let direction = if rng.gen::<bool>() {
+                    Onb::from_w(rec.normal).local(Vector::random_cosine_direction(rng))
+                } else {
+                    diffuse::sample_sheen(
+                        roughness.scalar(rec.u, rec.v, rec.p),
+                        rec.normal,
+                        -ray_in.direction.unit_vector(),
+                        rng,
+                    )
+                };
+                self.scatter_towards(ray_in, rec, direction)
+            }
+            Material::Subsurface(s) => s.scatter(ray_in, rec, rng),
         }
     }
 
-    pub fn emitted(&self, u: f64, v: f64, p: Point) -> Color {
+    pub fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> Color {
         match self {
-            Material::DiffuseLight { emit } => emit.value(u, v, p),
+            Material::Bump(b) => b.base().eval(ray_in, &b.shade(ray_in, rec), direction),
+            Material::Coated(c) => c.eval(ray_in, rec, direction),
+            Material::Conductor(c) => c.eval(ray_in, rec, direction),
+            Material::Isotropic { albedo } => albedo.value(rec.u, rec.v, rec.p) / (4.0 * PI),
+            Material::Lambertian { albedo } => {
+                let cosine = rec.normal.dot(direction.unit_vector());
+                if cosine <= 0.0 {
+                    Color::from(0)
+                } else {
+                    albedo.value(rec.u, rec.v, rec.p) * cosine / PI
+                }
+            }
+            Material::Merl(m) => m.eval(ray_in, rec, direction),
+            Material::Mix(m) => m.eval(ray_in, rec, direction),
+            Material::NormalMap(n) => n.base().eval(ray_in, &n.shade(ray_in, rec), direction),
+            Material::OrenNayar { albedo, sigma } => diffuse::oren_nayar(
+                albedo.value(rec.u, rec.v, rec.p),
+                sigma.scalar(rec.u, rec.v, rec.p),
+                rec.normal,
+                -ray_in.direction.unit_vector(),
+                direction.unit_vector(),
+            ),
+            Material::Principled(p) => p.eval(ray_in, rec, direction),
+            Material::RoughDielectric(d) => d.eval(ray_in, rec, direction),
+            Material::Sheen { albedo, roughness } => diffuse::sheen(
+                albedo.value(rec.u, rec.v, rec.p),
+                roughness.scalar(rec.u, rec.v, rec.p),
+                rec.normal,
+                -ray_in.direction.unit_vector(),
+                direction.unit_vector(),
+            ),
             _ => Color::from(0),
         }
     }
+
+    pub fn pdf(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> f64 {
+        match self {
+            Material::Bump(b) => b.base().pdf(ray_in, &b.shade(ray_in, rec), direction),
+            Material::Coated(c) => c.pdf(ray_in, rec, direction),
+            Material::Conductor(c) => c.pdf(ray_in, rec, direction),
+            Material::Isotropic { .. } => 1.0 / (4.0 * PI),
+            Material::Lambertian { .. } | Material::OrenNayar { .. } => {
+                let cosine = rec.normal.dot(direction.unit_vector());
+                if cosine <= 0.0 {
+                    0.0
+                } else {
+                    cosine / PI
+                }
+            }
+            Material::Merl(m) => m.pdf(ray_in, rec, direction),
+            Material::Mix(m) => m.pdf(ray_in, rec, direction),
+            Material::NormalMap(n) => n.base().pdf(ray_in, &n.shade(ray_in, rec), direction),
+            Material::Principled(p) => p.pdf(ray_in, rec, direction),
+            Material::RoughDielectric(d) => d.pdf(ray_in, rec, direction),
+            Material::Sheen { roughness, .. } => {
+                let cosine = rec.normal.dot(direction.unit_vector());
+                if cosine <= 0.0 {
+                    return 0.0;
+                }
+
+                let sheen = diffuse::sheen_pdf(
+                    roughness.scalar(rec.u, rec.v, rec.p),
+                    rec.normal,
+                    -ray_in.direction.unit_vector(),
+                    direction.unit_vector(),
+                );
+                0.5 * (cosine / PI + sheen)
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn scatter_towards(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        direction: Vector,
+    ) -> Option<ScatterRecord> {
+        let pdf = self.pdf(ray_in, rec, direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let scattered = Ray::new(rec.p, direction, ray_in.time);
+        let bsdf = self.eval(ray_in, rec, direction);
+        Some(ScatterRecord::new(scattered, bsdf, pdf))
+    }
+
+    pub fn albedo(&self, rec: &HitRecord) -> Color {
+        match self {
+            Material::Bump(b) => b.base().albedo(rec),
+            Material::Coated(c) => c.albedo(rec),
+            Material::Conductor(c) => c.albedo(),
+            Material::Dielectric(_) => Color::from(1),
+            Material::DiffuseLight(d) => d.radiance(rec),
+            Material::Isotropic { albedo }
+            | Material::Lambertian { albedo }
+            | Material::OrenNayar { albedo, .. }
+            | Material::Sheen { albedo, .. } => albedo.value(rec.u, rec.v, rec.p),
+            Material::Merl(m) => m.albedo(),
+            Material::Metal { albedo, .. } => *albedo,
+            Material::Mix(m) => m.albedo(rec),
+            Material::NormalMap(n) => n.base().albedo(rec),
+            Material::Principled(p) => p.albedo(rec),
+            Material::RoughDielectric(_) => Color::from(1),
+            Material::Subsurface(s) => s.albedo(),
+        }
+    }
+
+    pub fn emitted(&self, ray_in: &Ray, rec: &HitRecord) -> Color {
+        match self {
+            Material::DiffuseLight(d) => d.emitted(rec),
+            Material::Bump(b) => b.base().emitted(ray_in, rec),
+            Material::Mix(m) => m.emitted(ray_in, rec),
+            Material::NormalMap(n) => n.base().emitted(ray_in, rec),
+            _ => Color::from(0),
+        }
+    }
+
+    pub fn emitted_spectrum(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        wavelengths: &Wavelengths,
+    ) -> Color {
+        match self {
+            Material::DiffuseLight(d) => d.emitted_spectrum(rec, wavelengths),
+            Material::Bump(b) => b.base().emitted_spectrum(ray_in, rec, wavelengths),
+            Material::Mix(m) => m.emitted_spectrum(ray_in, rec, wavelengths),
+            Material::NormalMap(n) => n.base().emitted_spectrum(ray_in, rec, wavelengths),
+            _ => Color::from(0),
+        }
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        match self {
+            Material::Bump(b) => b.base().is_dispersive(),
+            Material::Conductor(c) => c.is_dispersive(),
+            Material::Dielectric(d) => d.is_dispersive(),
+            Material::Mix(m) => m.is_dispersive(),
+            Material::NormalMap(n) => n.base().is_dispersive(),
+            _ => false,
+        }
+    }
+
+    // Whether any of the scattering at `rec` is left to eval and pdf rather than
+    // to delta lobes, which lights can't be sampled against. This holds for the
+    // material as a whole, whichever lobe a particular scatter picks.
+    pub fn has_non_delta(&self, rec: &HitRecord) -> bool {
+        match self {
+            Material::Bump(b) => b.base().has_non_delta(rec),
+            Material::Conductor(c) => c.has_non_delta(),
+            Material::Dielectric(_)
+            | Material::DiffuseLight(_)
+            | Material::Metal { .. }
+            | Material::Subsurface(_) => false,
+            Material::Mix(m) => m.has_non_delta(rec),
+            Material::NormalMap(n) => n.base().has_non_delta(rec),
+            Material::RoughDielectric(d) => d.has_non_delta(rec),
+            Material::Coated(_)
+            | Material::Isotropic { .. }
+            | Material::Lambertian { .. }
+            | Material::Merl(_)
+            | Material::OrenNayar { .. }
+            | Material::Principled(_)
+            | Material::Sheen { .. } => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::check_bsdf;
+    use super::Material;
+    use crate::core::Vector;
+    use crate::textures::SolidColor;
+
+    #[test]
+    fn lambertian_is_sampled_by_its_pdf() {
+        let material = Material::Lambertian {
+            albedo: SolidColor::new_texture(0.7, 0.5, 0.3),
+        };
+        check_bsdf(&material, Vector::new(1, 0, 1));
+    }
+
+    #[test]
+    fn isotropic_is_sampled_by_its_pdf() {
+        let material = Material::Isotropic {
+            albedo: SolidColor::from(0.6),
+        };
+        check_bsdf(&material, Vector::new(0, 1, 1));
+    }
 }
@@ -0,0 +1,107 @@
+use super::Material;
+use crate::core::{Onb, Ray, Vector};
+use crate::objects::HitRecord;
+use crate::textures::Texture;
+
+// Replaces the shading normal of `base` with one read from a tangent-space
+// normal map, where red, green and blue encode the dpdu, dpdv and normal axes.
+#[derive(Clone)]
+pub struct NormalMap {
+    base: Box<Material>,
+    map: Texture,
+}
+
+impl NormalMap {
+    pub fn new_material(base: Material, map: Texture) -> Material {
+        Material::NormalMap(Self {
+            base: Box::new(base),
+            map,
+        })
+    }
+
+    pub fn base(&self) -> &Material {
+        &self.base
+    }
+
+    pub fn shade<'m>(&self, ray_in: &Ray, rec: &HitRecord<'m>) -> HitRecord<'m> {
+        // The map describes the outward side, so the frame is built there and the
+        // result turned round for back faces; a frame built on the flipped normal
+        // would be mirrored.
+        let n = if rec.front_face {
+            rec.normal
+        } else {
+            -rec.normal
+        };
+        let tangent = rec.dpdu - n * n.dot(rec.dpdu);
+        let tangent = if tangent.length_squared() < 1e-12 {
+            Onb::from_w(n).u()
+        } else {
+            tangent.unit_vector()
+        };
+        let bitangent = n.cross(tangent);
+
+        let texel = self.map.value(rec.u, rec.v, rec.p) * 2.0 - Vector::from(1);
+        let normal = tangent * texel.x() + bitangent * texel.y() + n * texel.z();
+        if normal.length_squared() == 0.0 {
+            return *rec;
+        }
+
+        let normal = normal.unit_vector();
+        let normal = if rec.front_face { normal } else { -normal };
+        rec.with_shading_normal(ray_in, normal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::{hit, ray_from};
+    use super::super::Material;
+    use super::NormalMap;
+    use crate::core::{Color, Vector};
+    use crate::textures::SolidColor;
+
+    fn normal_map(texel: Color) -> Material {
+        let base = Material::Lambertian {
+            albedo: SolidColor::from(0.5),
+        };
+        NormalMap::new_material(
+            base,
+            SolidColor::new_texture(texel.x(), texel.y(), texel.z()),
+        )
+    }
+
+    fn shaded_normal(material: &Material, front_face: bool) -> Vector {
+        let map = match material {
+            Material::NormalMap(map) => map,
+            _ => unreachable!(),
+        };
+        let mut rec = hit(material);
+        let mut towards_viewer = Vector::new(0, 0, 1);
+        if !front_face {
+            rec.front_face = false;
+            rec.normal = -rec.normal;
+            towards_viewer = -towards_viewer;
+        }
+        map.shade(&ray_from(towards_viewer), &rec).normal
+    }
+
+    #[test]
+    fn flat_normal_map_leaves_the_normal_unchanged() {
+        let material = normal_map(Color::new(0.5, 0.5, 1.0));
+        for &front_face in [true, false].iter() {
+            let expected = hit(&material).normal * if front_face { 1.0 } else { -1.0 };
+            let normal = shaded_normal(&material, front_face);
+            assert!((normal - expected).length() < 1e-12, "{:?}", normal);
+        }
+    }
+
+    #[test]
+    fn back_faces_see_the_same_tilted_surface() {
+        let material = normal_map(Color::new(0.8, 0.3, 0.9));
+        let front = shaded_normal(&material, true);
+        let back = shaded_normal(&material, false);
+
+        assert!(front.x() > 0.0 && front.y() < 0.0);
+        assert!((front + back).length() < 1e-12, "{:?} {:?}", front, back);
+    }
+}
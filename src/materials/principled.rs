@@ -0,0 +1,294 @@
+use super::microfacet::{Ggx, Gtr1};
+use super::rough_dielectric::{half_vector, refraction_jacobian};
+use super::{fresnel, Material, ScatterRecord};
+use crate::core::{Color, Onb, Ray, Vector};
+use crate::objects::HitRecord;
+use crate::textures::{SolidColor, Texture};
+use crate::utils::clamp;
+
+use std::f64::consts::PI;
+
+use rand::prelude::*;
+
+// Disney "principled" BSDF (Burley 2012, 2015). Every parameter other than
+// `ior` is a texture.
+#[derive(Clone)]
+pub struct Principled {
+    pub base_color: Texture,
+    pub metallic: Texture,
+    pub roughness: Texture,
+    pub specular: Texture,
+    pub clearcoat: Texture,
+    pub clearcoat_gloss: Texture,
+    pub sheen: Texture,
+    pub transmission: Texture,
+    pub ior: f64,
+}
+
+// Parameters resolved at a hit point, in the local shading frame.
+struct Lobes {
+    onb: Onb,
+    wo: Vector,
+    eta: f64,
+    base_color: Color,
+    specular_color: Color,
+    sheen_color: Color,
+    roughness: f64,
+    diffuse: f64,
+    clearcoat: f64,
+    transmission: f64,
+    specular: Ggx,
+    coat: Gtr1,
+    // Selection probabilities for the diffuse, specular, clearcoat and
+    // transmission lobes.
+    weights: [f64; 4],
+}
+
+impl Default for Principled {
+    fn default() -> Self {
+        Self {
+            base_color: SolidColor::from(0.8),
+            metallic: SolidColor::from(0),
+            roughness: SolidColor::from(0.5),
+            specular: SolidColor::from(0.5),
+            clearcoat: SolidColor::from(0),
+            clearcoat_gloss: SolidColor::from(1),
+            sheen: SolidColor::from(0),
+            transmission: SolidColor::from(0),
+            ior: 1.5,
+        }
+    }
+}
+
+impl Principled {
+    pub fn new_material(base_color: Texture, metallic: f64, roughness: f64) -> Material {
+        Material::Principled(Self {
+            base_color,
+            metallic: SolidColor::from(metallic),
+            roughness: SolidColor::from(roughness),
+            ..Self::default()
+        })
+    }
+
+    pub fn albedo(&self, rec: &HitRecord) -> Color {
+        self.base_color.value(rec.u, rec.v, rec.p)
+    }
+
+    fn lobes(&self, ray_in: &Ray, rec: &HitRecord) -> Lobes {
+        let scalar = |t: &Texture| clamp(t.scalar(rec.u, rec.v, rec.p), 0.0, 1.0);
+
+        let base_color = self.base_color.value(rec.u, rec.v, rec.p);
+        let metallic = scalar(&self.metallic);
+        // Perfectly smooth lobes would need delta handling in every mixture term.
+        let roughness = scalar(&self.roughness).max(0.05);
+        let specular = scalar(&self.specular);
+        let clearcoat = scalar(&self.clearcoat);
+        let clearcoat_gloss = scalar(&self.clearcoat_gloss);
+        let sheen = scalar(&self.sheen);
+        let transmission = scalar(&self.transmission);
+
+        let luminance = base_color.dot(Color::new(0.2126, 0.7152, 0.0722));
+        let tint = if luminance > 0.0 {
+            base_color / luminance
+        } else {
+            Color::from(1)
+        };
+
+        let dielectric = (1.0 - metallic) * (1.0 - transmission);
+        let transmissive = (1.0 - metallic) * transmission;
+        let weights = [
+            dielectric,
+            1.0 - transmissive,
+            0.25 * clearcoat,
+            transmissive,
+        ];
+        let total: f64 = weights.iter().sum();
+
+        let onb = Onb::from_w(rec.normal);
+        Lobes {
+            onb,
+            wo: onb.to_local(-ray_in.direction.unit_vector()),
+            eta: if rec.front_face {
+                self.ior
+            } else {
+                1.0 / self.ior
+            },
+            base_color,
+            specular_color: Color::from(0.08 * specular) * (1.0 - metallic) + base_color * metallic,
+            // Sheen tint is fixed half way between white and the base hue.
+            sheen_color: (Color::from(1) + tint) * 0.5 * sheen * (1.0 - metallic),
+            roughness,
+            diffuse: dielectric,
+            clearcoat,
+            transmission: transmissive,
+            specular: Ggx::from_roughness(roughness, roughness),
+            coat: Gtr1::new(0.1 + (0.001 - 0.1) * clearcoat_gloss),
+            weights: [
+                weights[0] / total,
+                weights[1] / total,
+                weights[2] / total,
+                weights[3] / total,
+            ],
+        }
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        let lobes = self.lobes(ray_in, rec);
+        let wo = lobes.wo;
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let [diffuse, specular, clearcoat, _] = lobes.weights;
+        let u = rng.gen::<f64>();
+        let wi = if u < diffuse {
+            Vector::random_cosine_direction(rng)
+        } else if u < diffuse + specular {
+            (-wo).reflect(lobes.specular.sample_wh(wo, rng))
+        } else if u < diffuse + specular + clearcoat {
+            let wh = lobes.coat.sample_wh(rng);
+            let wh = if wo.dot(wh) < 0.0 { -wh } else { wh };
+            (-wo).reflect(wh)
+        } else {
+            let wh = lobes.specular.sample_wh(wo, rng);
+            if rng.gen::<f64>() < fresnel::dielectric(wo.dot(wh), lobes.eta) {
+                (-wo).reflect(wh)
+            } else {
+                let wi = (-wo).refract(wh, 1.0 / lobes.eta);
+                if wi.z() >= 0.0 {
+                    return None;
+                }
+                wi
+            }
+        };
+        // Only transmission may leave below the surface; `eval` and `pdf` would
+        // take a reflection there for a refraction.
+        if wi.z() < 0.0 && u < diffuse + specular + clearcoat {
+            return None;
+        }
+
+        let pdf = lobes.pdf(wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let scattered = Ray::new(rec.p, lobes.onb.local(wi), ray_in.time);
+        Some(ScatterRecord::new(scattered, lobes.eval(wi), pdf))
+    }
+
+    pub fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> Color {
+        let lobes = self.lobes(ray_in, rec);
+        lobes.eval(lobes.onb.to_local(direction.unit_vector()))
+    }
+
+    pub fn pdf(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> f64 {
+        let lobes = self.lobes(ray_in, rec);
+        lobes.pdf(lobes.onb.to_local(direction.unit_vector()))
+    }
+}
+
+impl Lobes {
+    fn eval(&self, wi: Vector) -> Color {
+        let wo = self.wo;
+        let (diffuse, transmission) = (self.diffuse, self.transmission);
+        if wo.z() <= 0.0 || wi.z() == 0.0 {
+            return Color::from(0);
+        }
+
+        let wh = match half_vector(wo, wi, self.eta) {
+            Some(wh) => wh,
+            None => return Color::from(0),
+        };
+        let cos_d = wo.dot(wh);
+        let g = self.specular.g(wo, wi);
+        let d = self.specular.d(wh);
+        let dielectric = fresnel::dielectric(cos_d, self.eta);
+
+        if wi.z() < 0.0 {
+            let btdf = (1.0 - dielectric) * d * g * cos_d.abs() / wo.z()
+                * refraction_jacobian(wo, wi, wh, self.eta);
+            return self.base_color * (transmission * btdf);
+        }
+
+        // Burley's retro-reflective diffuse with a Schlick-weighted sheen rim.
+        let fd90 = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+        let fl = (1.0 - wi.z()).powi(5);
+        let fv = (1.0 - wo.z()).powi(5);
+        let retro = (1.0 + (fd90 - 1.0) * fl) * (1.0 + (fd90 - 1.0) * fv);
+        let rim = (1.0 - cos_d).powi(5);
+        let lambert = (self.base_color * (retro / PI) * diffuse + self.sheen_color * rim) * wi.z();
+
+        let specular = fresnel::schlick(cos_d, self.specular_color)
+            * (1.0 - transmission)
+            * (d * g / (4.0 * wo.z()));
+
+        let coat_g = Ggx::new(0.25, 0.25).g(wo, wi);
+        let coat_f = fresnel::schlick(cos_d, Color::from(0.04)).x();
+        let coat = 0.25 * self.clearcoat * self.coat.d(wh) * coat_g * coat_f / (4.0 * wo.z());
+
+        let glass = transmission * dielectric * d * g / (4.0 * wo.z());
+
+        lambert + specular + Color::from(coat + glass)
+    }
+
+    fn pdf(&self, wi: Vector) -> f64 {
+        let wo = self.wo;
+        let [diffuse, specular, clearcoat, transmission] = self.weights;
+        if wo.z() <= 0.0 || wi.z() == 0.0 {
+            return 0.0;
+        }
+
+        let wh = match half_vector(wo, wi, self.eta) {
+            Some(wh) => wh,
+            None => return 0.0,
+        };
+        let cos_d = wo.dot(wh);
+        let pdf_wh = self.specular.pdf(wo, wh);
+        let dielectric = fresnel::dielectric(cos_d, self.eta);
+
+        if wi.z() < 0.0 {
+            return transmission
+                * (1.0 - dielectric)
+                * pdf_wh
+                * refraction_jacobian(wo, wi, wh, self.eta);
+        }
+
+        diffuse * wi.z() / PI
+            + (specular + transmission * dielectric) * pdf_wh / (4.0 * cos_d)
+            + clearcoat * self.coat.pdf(wh) / (4.0 * cos_d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::check_bsdf;
+    use super::super::Material;
+    use super::Principled;
+    use crate::core::Vector;
+    use crate::textures::SolidColor;
+
+    #[test]
+    fn principled_is_sampled_by_its_pdf() {
+        let material = Material::Principled(Principled {
+            base_color: SolidColor::new_texture(0.8, 0.4, 0.2),
+            metallic: SolidColor::from(0.3),
+            clearcoat: SolidColor::from(1),
+            clearcoat_gloss: SolidColor::from(0.5),
+            sheen: SolidColor::from(1),
+            transmission: SolidColor::from(0.5),
+            ..Principled::default()
+        });
+        check_bsdf(&material, Vector::new(1, 0, 1));
+    }
+
+    #[test]
+    fn metallic_principled_is_sampled_by_its_pdf() {
+        let material = Principled::new_material(SolidColor::new_texture(0.9, 0.6, 0.3), 1.0, 0.4);
+        check_bsdf(&material, Vector::new(1, 0, 2));
+    }
+}
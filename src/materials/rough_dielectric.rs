@@ -0,0 +1,215 @@
+use super::microfacet::Ggx;
+use super::{fresnel, Material, ScatterRecord};
+use crate::core::{Color, Onb, Ray, Vector};
+use crate::objects::HitRecord;
+use crate::textures::{SolidColor, Texture};
+
+use rand::prelude::*;
+
+// Microfacet reflection and transmission after Walter et al. 2007.
+#[derive(Clone)]
+pub struct RoughDielectric {
+    ref_index: f64,
+    roughness: Texture,
+}
+
+struct LocalFrame {
+    onb: Onb,
+    wo: Vector,
+    eta: f64,
+    distribution: Ggx,
+}
+
+impl RoughDielectric {
+    pub fn new_material(ref_index: f64, roughness: f64) -> Material {
+        Self::new_textured_material(ref_index, SolidColor::from(roughness))
+    }
+
+    pub fn new_textured_material(ref_index: f64, roughness: Texture) -> Material {
+        Material::RoughDielectric(Self {
+            ref_index,
+            roughness,
+        })
+    }
+
+    pub fn has_non_delta(&self, rec: &HitRecord) -> bool {
+        let roughness = self.roughness.scalar(rec.u, rec.v, rec.p);
+        !Ggx::from_roughness(roughness, roughness).is_smooth()
+    }
+
+    fn frame(&self, ray_in: &Ray, rec: &HitRecord) -> LocalFrame {
+        let onb = Onb::from_w(rec.normal);
+        let roughness = self.roughness.scalar(rec.u, rec.v, rec.p);
+
+        LocalFrame {
+            onb,
+            wo: onb.to_local(-ray_in.direction.unit_vector()),
+            eta: if rec.front_face {
+                self.ref_index
+            } else {
+                1.0 / self.ref_index
+            },
+            distribution: Ggx::from_roughness(roughness, roughness),
+        }
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        let LocalFrame {
+            onb,
+            wo,
+            eta,
+            distribution,
+        } = self.frame(ray_in, rec);
+        if wo.z() <= 0.0 {
+            return None;
+        }
+
+        let wh = if distribution.is_smooth() {
+            Vector::new(0, 0, 1)
+        } else {
+            distribution.sample_wh(wo, rng)
+        };
+
+        let reflectance = fresnel::dielectric(wo.dot(wh), eta);
+        let reflected = rng.gen::<f64>() < reflectance;
+        let wi = if reflected {
+            (-wo).reflect(wh)
+        } else {
+            (-wo).refract(wh, 1.0 / eta)
+        };
+        // A steep microfacet can send either onto the wrong side of the surface,
+        // where `eval` and `pdf` would take it for the other lobe.
+        if (wi.z() > 0.0) != reflected {
+            return None;
+        }
+
+        let direction = onb.local(wi);
+        let scattered = Ray::new(rec.p, direction, ray_in.time);
+
+        if distribution.is_smooth() {
+            return Some(ScatterRecord::specular(scattered, Color::from(1)));
+        }
+
+        let pdf = self.pdf(ray_in, rec, direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord::new(
+            scattered,
+            self.eval(ray_in, rec, direction),
+            pdf,
+        ))
+    }
+
+    pub fn eval(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> Color {
+        let LocalFrame {
+            onb,
+            wo,
+            eta,
+            distribution,
+        } = self.frame(ray_in, rec);
+        let wi = onb.to_local(direction.unit_vector());
+        if distribution.is_smooth() || wo.z() <= 0.0 || wi.z() == 0.0 {
+            return Color::from(0);
+        }
+
+        let wh = match half_vector(wo, wi, eta) {
+            Some(wh) => wh,
+            None => return Color::from(0),
+        };
+        let reflectance = fresnel::dielectric(wo.dot(wh), eta);
+        let d = distribution.d(wh);
+        let g = distribution.g(wo, wi);
+
+        if wi.z() > 0.0 {
+            Color::from(reflectance * d * g / (4.0 * wo.z()))
+        } else {
+            Color::from(
+                (1.0 - reflectance) * d * g * wo.dot(wh).abs() / wo.z()
+                    * refraction_jacobian(wo, wi, wh, eta),
+            )
+        }
+    }
+
+    pub fn pdf(&self, ray_in: &Ray, rec: &HitRecord, direction: Vector) -> f64 {
+        let LocalFrame {
+            onb,
+            wo,
+            eta,
+            distribution,
+        } = self.frame(ray_in, rec);
+        let wi = onb.to_local(direction.unit_vector());
+        if distribution.is_smooth() || wo.z() <= 0.0 || wi.z() == 0.0 {
+            return 0.0;
+        }
+
+        let wh = match half_vector(wo, wi, eta) {
+            Some(wh) => wh,
+            None => return 0.0,
+        };
+        let reflectance = fresnel::dielectric(wo.dot(wh), eta);
+        let pdf_wh = distribution.pdf(wo, wh);
+
+        if wi.z() > 0.0 {
+            reflectance * pdf_wh / (4.0 * wo.dot(wh))
+        } else {
+            (1.0 - reflectance) * pdf_wh * refraction_jacobian(wo, wi, wh, eta)
+        }
+    }
+}
+
+// Microfacet normal for a reflected or refracted pair, oriented to the side of `wo`.
+pub(super) fn half_vector(wo: Vector, wi: Vector, eta: f64) -> Option<Vector> {
+    let wh = if wi.z() > 0.0 { wo + wi } else { wo + wi * eta };
+    if wh.length_squared() == 0.0 {
+        return None;
+    }
+
+    let wh = wh.unit_vector();
+    let wh = if wh.z() < 0.0 { -wh } else { wh };
+
+    if wi.z() < 0.0 && (wo.dot(wh) <= 0.0 || wi.dot(wh) >= 0.0) {
+        return None;
+    }
+
+    Some(wh)
+}
+
+// Change of density |dwh/dwi| from half vectors to refracted directions.
+//
+// Transmitted radiance is not rescaled by 1/eta² at the interface, the same
+// convention as the smooth `Dielectric` whose refraction has unit weight. The
+// BTDF returned by `eval` therefore carries this same factor as `pdf`, so the two
+// cancel in `scatter`'s weight and next event estimation sees the same BTDF.
+pub(super) fn refraction_jacobian(wo: Vector, wi: Vector, wh: Vector, eta: f64) -> f64 {
+    let denominator = wo.dot(wh) + eta * wi.dot(wh);
+    eta * eta * wi.dot(wh).abs() / (denominator * denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::check_bsdf;
+    use super::RoughDielectric;
+    use crate::core::Vector;
+
+    #[test]
+    fn rough_dielectric_is_sampled_by_its_pdf() {
+        let material = RoughDielectric::new_material(1.5, 0.5);
+        check_bsdf(&material, Vector::new(1, 0, 1));
+        check_bsdf(&material, Vector::new(0, 0, 1));
+    }
+
+    #[test]
+    fn very_rough_dielectric_is_sampled_by_its_pdf() {
+        check_bsdf(
+            &RoughDielectric::new_material(1.5, 0.9),
+            Vector::new(1, 0, 0.3),
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use crate::core::{Color, Ray};
+
+pub struct ScatterRecord {
+    pub ray: Ray,
+    // BSDF value for `ray`, already multiplied by the cosine foreshortening term.
+    pub bsdf: Color,
+    // Solid angle density `ray.direction` was sampled with; 1 for specular lobes.
+    pub pdf: f64,
+    pub specular: bool,
+}
+
+impl ScatterRecord {
+    pub fn new(ray: Ray, bsdf: Color, pdf: f64) -> Self {
+        Self {
+            ray,
+            bsdf,
+            pdf,
+            specular: false,
+        }
+    }
+
+    pub fn specular(ray: Ray, attenuation: Color) -> Self {
+        Self {
+            ray,
+            bsdf: attenuation,
+            pdf: 1.0,
+            specular: true,
+        }
+    }
+
+    pub fn weight(&self) -> Color {
+        self.bsdf / self.pdf
+    }
+}
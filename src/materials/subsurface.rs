@@ -0,0 +1,269 @@
+use super::{Material, ScatterRecord};
+use crate::core::{Color, Ray, Vector};
+use crate::objects::{free_flight_distance, HitRecord, Object};
+use crate::utils::schlick;
+
+use std::sync::Arc;
+
+use rand::prelude::*;
+
+// Scattering events before a walk is abandoned.
+const MAX_BOUNCES: usize = 1024;
+
+// Random-walk subsurface scattering: light refracts into `boundary`, scatters
+// isotropically through a homogeneous medium and leaves wherever it next
+// refracts out. The medium is described per channel by its single scattering
+// albedo and the mean distance between collisions. Hits are expected in the
+// boundary's own space, up to the `Translate` and `RotateY` wrappers around it.
+#[derive(Clone)]
+pub struct Subsurface {
+    boundary: Arc<dyn Object>,
+    ref_index: f64,
+    albedo: Color,
+    mean_free_path: Color,
+}
+
+impl Subsurface {
+    pub fn new_material(
+        boundary: Arc<dyn Object>,
+        ref_index: f64,
+        albedo: Color,
+        mean_free_path: Color,
+    ) -> Material {
+        Material::Subsurface(Self {
+            boundary,
+            ref_index,
+            albedo,
+            mean_free_path,
+        })
+    }
+
+    pub fn albedo(&self) -> Color {
+        self.albedo
+    }
+
+    pub fn scatter(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        rng: &mut ThreadRng,
+    ) -> Option<ScatterRecord> {
+        // The walk runs in the boundary's own space, which the hit may have been
+        // moved out of by `Translate` or `RotateY`.
+        let to_world = rec.to_world;
+        let p = to_world.inverse_point(rec.p);
+        let direction = to_world.inverse_vector(ray_in.direction.unit_vector());
+        let normal = to_world.inverse_vector(rec.normal);
+
+        let ray = if rec.front_face {
+            let cos_theta = (-direction).dot(normal).min(1.0);
+            if rng.gen::<f64>() < schlick(cos_theta, 1.0 / self.ref_index) {
+                let reflected = Ray::new(
+                    rec.p,
+                    to_world.vector(direction.reflect(normal)),
+                    ray_in.time,
+                );
+                return Some(ScatterRecord::specular(reflected, Color::from(1)));
+            }
+
+            Ray::new(
+                p,
+                direction.refract(normal, 1.0 / self.ref_index),
+                ray_in.time,
+            )
+        } else {
+            // The ray was already inside the medium, so it meets the surface just
+            // as the end of a walk would.
+            match self.refract_out(direction, normal, rng) {
+                Some(refracted) => {
+                    let scattered = Ray::new(rec.p, to_world.vector(refracted), ray_in.time);
+                    return Some(ScatterRecord::specular(scattered, Color::from(1)));
+                }
+                None => Ray::new(p, direction.reflect(normal), ray_in.time),
+            }
+        };
+
+        let (exit, throughput) = self.walk(ray, rng)?;
+        let scattered = Ray::new(
+            to_world.point(exit.origin),
+            to_world.vector(exit.direction),
+            ray_in.time,
+        );
+        Some(ScatterRecord::specular(scattered, throughput))
+    }
+
+    // Follows a ray inside the boundary until it leaves, returning the outgoing
+    // ray and the weight picked up along the way.
+    //
+    // Distances are all sampled from one channel, picked per walk, and weighted
+    // by the average density the whole walk has over all of them. Weighting each
+    // collision separately would multiply up ratios without bound on long walks.
+    fn walk(&self, mut ray: Ray, rng: &mut ThreadRng) -> Option<(Ray, Color)> {
+        let sigma_t = Color::from(1) / self.mean_free_path;
+        let channel = rng.gen_range(0, 3);
+
+        // Logs, so that long walks don't underflow.
+        let mut log_density = Color::from(0);
+        let mut albedo = Color::from(1);
+        // Rays leaving the surface need the usual epsilon; collisions are off the
+        // surface, so one just inside it would otherwise never find its way out.
+        let mut t_min = 0.001;
+        for _ in 0..MAX_BOUNCES {
+            let exit = self.boundary.hit(&ray, t_min..f64::INFINITY, rng)?;
+            let distance = exit.t * ray.direction.length();
+            let t = free_flight_distance(sigma_t[channel], rng);
+
+            if t < distance {
+                for c in 0..3 {
+                    log_density[c] += sigma_t[c].ln() - sigma_t[c] * t;
+                }
+                albedo *= self.albedo;
+
+                let origin = ray.at(t / ray.direction.length());
+                ray = Ray::new(origin, Vector::random_unit_vector(rng), ray.time);
+                t_min = 0.0;
+                continue;
+            }
+
+            for c in 0..3 {
+                log_density[c] -= sigma_t[c] * distance;
+            }
+
+            let direction = ray.direction.unit_vector();
+            match self.refract_out(direction, exit.normal, rng) {
+                Some(refracted) => {
+                    let throughput = albedo * balance(log_density);
+                    return Some((Ray::new(exit.p, refracted, ray.time), throughput));
+                }
+                None => {
+                    ray = Ray::new(exit.p, direction.reflect(exit.normal), ray.time);
+                    t_min = 0.001;
+                }
+            }
+        }
+
+        None
+    }
+
+    // Direction of a ray leaving the medium through a surface with the inward
+    // facing `normal`, or `None` if it is reflected back inside.
+    fn refract_out(
+        &self,
+        direction: Vector,
+        normal: Vector,
+        rng: &mut ThreadRng,
+    ) -> Option<Vector> {
+        let cos_theta = (-direction).dot(normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        if self.ref_index * sin_theta > 1.0 || rng.gen::<f64>() < schlick(cos_theta, self.ref_index)
+        {
+            return None;
+        }
+        Some(direction.refract(normal, self.ref_index))
+    }
+}
+
+// Each channel's density over their average, from their logs.
+fn balance(log_density: Color) -> Color {
+    let max = log_density.max_component();
+    let density = Color::new(
+        (log_density.x() - max).exp(),
+        (log_density.y() - max).exp(),
+        (log_density.z() - max).exp(),
+    );
+    density * 3.0 / (density.x() + density.y() + density.z())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Material;
+    use crate::core::{Color, Point, Ray, Vector};
+    use crate::objects::{Boxx, Object, RotateY, Sphere, SubsurfaceMedium, Translate};
+    use crate::textures::SolidColor;
+    use crate::utils::{degrees_to_radians, schlick};
+
+    fn boundary_material() -> Material {
+        Material::Lambertian {
+            albedo: SolidColor::from(0.5),
+        }
+    }
+
+    #[test]
+    fn lossless_medium_returns_all_light_but_the_fresnel_reflection() {
+        let sphere = Sphere::new(Point::from(0), 1.0, boundary_material());
+        let mean_free_path = Color::new(0.2, 0.35, 0.5);
+        let medium = SubsurfaceMedium::new(sphere, 1.5, Color::from(1), mean_free_path);
+        let mut rng = rand::thread_rng();
+
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1), 0.0);
+        let rec = medium.hit(&ray, 0.001..f64::INFINITY, &mut rng).unwrap();
+
+        let samples = 20_000;
+        let (mut total, mut walked) = (Color::from(0), Color::from(0));
+        for _ in 0..samples {
+            let srec = match rec.material.scatter(&ray, &rec, &mut rng) {
+                Some(srec) => srec,
+                None => continue,
+            };
+            total += srec.weight();
+            // Light reflected by the surface leaves from where it arrived.
+            if (srec.ray.origin - rec.p).length() > 1e-6 {
+                walked += srec.weight();
+            }
+        }
+
+        let fresnel = schlick(1.0, 1.0 / 1.5);
+        for c in 0..3 {
+            let total = total[c] / samples as f64;
+            let walked = walked[c] / samples as f64;
+            assert!((total - 1.0).abs() < 0.03, "{}", total);
+            assert!((walked - (1.0 - fresnel)).abs() < 0.03, "{}", walked);
+        }
+    }
+
+    #[test]
+    fn walks_leave_through_the_transformed_boundary() {
+        let unit_box = Boxx::new(Point::from(-1), Point::from(1), boundary_material());
+        let medium = SubsurfaceMedium::new(unit_box, 1.3, Color::from(0.9), Color::from(0.3));
+        let (angle, offset) = (30.0, Vector::new(10, 2, 0));
+        let world = Translate::new(RotateY::new(medium, angle), offset);
+        let mut rng = rand::thread_rng();
+
+        // Into the box's own space, undoing the wrappers.
+        let (sin_theta, cos_theta) = degrees_to_radians(angle).sin_cos();
+        let to_box = |p: Point| {
+            let p = p - offset;
+            Point::new(
+                cos_theta * p.x() - sin_theta * p.z(),
+                p.y(),
+                sin_theta * p.x() + cos_theta * p.z(),
+            )
+        };
+        let inside = |p: Point| {
+            let p = to_box(p);
+            p.x().abs().max(p.y().abs()).max(p.z().abs()) < 1.0
+        };
+
+        let ray = Ray::new(Point::new(10, 2, -5), Vector::new(0, 0, 1), 0.0);
+        let rec = world.hit(&ray, 0.001..f64::INFINITY, &mut rng).unwrap();
+
+        for _ in 0..1_000 {
+            let srec = match rec.material.scatter(&ray, &rec, &mut rng) {
+                Some(srec) => srec,
+                None => continue,
+            };
+            let exit = srec.ray.origin;
+            let on_surface = to_box(exit);
+            let extent = on_surface
+                .x()
+                .abs()
+                .max(on_surface.y().abs())
+                .max(on_surface.z().abs());
+            assert!((extent - 1.0).abs() < 1e-6, "{:?}", on_surface);
+
+            let step = srec.ray.direction.unit_vector() * 1e-3;
+            assert!(inside(exit - step) && !inside(exit + step));
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::Material;
+use crate::core::{Point, Ray, Vector};
+use crate::objects::{HitRecord, ToWorld};
+
+use std::f64::consts::PI;
+
+// Hit at the origin on a surface facing +z.
+pub fn hit(material: &Material) -> HitRecord<'_> {
+    HitRecord {
+        p: Point::from(0),
+        normal: Vector::new(0, 0, 1),
+        dpdu: Vector::new(1, 0, 0),
+        dpdv: Vector::new(0, 1, 0),
+        material,
+        t: 1.0,
+        u: 0.5,
+        v: 0.5,
+        front_face: true,
+        object_id: 0,
+        to_world: ToWorld::identity(),
+    }
+}
+
+// Ray arriving at the origin from `towards_viewer`.
+pub fn ray_from(towards_viewer: Vector) -> Ray {
+    let towards_viewer = towards_viewer.unit_vector();
+    Ray::new(Point::from(0) + towards_viewer, -towards_viewer, 0.0)
+}
+
+// Midpoint rule in polar angle and azimuth, fine enough near the poles for
+// lobes pointing straight up or down.
+fn integrate<F: FnMut(Vector) -> f64>(mut f: F) -> f64 {
+    let (rows, columns) = (500, 1000);
+    let (d_theta, d_phi) = (PI / rows as f64, 2.0 * PI / columns as f64);
+    let mut sum = 0.0;
+    for i in 0..rows {
+        let (sin_theta, cos_theta) = ((i as f64 + 0.5) * d_theta).sin_cos();
+        for j in 0..columns {
+            let phi = (j as f64 + 0.5) * d_phi;
+            let w = Vector::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+            sum += f(w) * sin_theta;
+        }
+    }
+    sum * d_theta * d_phi
+}
+
+// Checks that `scatter` agrees with `eval` and `pdf` and draws directions with
+// that density. Samplers may lose directions below the surface, and delta lobes
+// are left out of `eval` and `pdf`, so `pdf` must integrate over the sphere to
+// the fraction of scatters that succeed with a non-delta lobe.
+pub fn check_bsdf(material: &Material, towards_viewer: Vector) {
+    let rec = hit(material);
+    let ray_in = ray_from(towards_viewer);
+    let mut rng = rand::thread_rng();
+    assert!(material.has_non_delta(&rec));
+
+    let samples = 200_000;
+    let mut scattered = 0;
+    let mut estimate = 0.0;
+    for _ in 0..samples {
+        let srec = match material.scatter(&ray_in, &rec, &mut rng) {
+            Some(srec) => srec,
+            None => continue,
+        };
+        if srec.specular {
+            continue;
+        }
+
+        let direction = srec.ray.direction;
+        let pdf = material.pdf(&ray_in, &rec, direction);
+        let bsdf = material.eval(&ray_in, &rec, direction);
+        assert!(
+            (srec.pdf - pdf).abs() <= 1e-6 * pdf,
+            "{} != {}",
+            srec.pdf,
+            pdf
+        );
+        assert!(
+            (srec.bsdf - bsdf).length() <= 1e-6 * bsdf.length(),
+            "{:?} != {:?}",
+            srec.bsdf,
+            bsdf
+        );
+
+        scattered += 1;
+        estimate += srec.weight().y();
+    }
+    let scattered = scattered as f64 / samples as f64;
+    estimate /= samples as f64;
+
+    let total = integrate(|w| material.pdf(&ray_in, &rec, w));
+    assert!(
+        (total - scattered).abs() < 0.01,
+        "pdf integrates to {} but {} of samples scatter",
+        total,
+        scattered
+    );
+
+    let albedo = integrate(|w| material.eval(&ray_in, &rec, w).y());
+    assert!(
+        (estimate - albedo).abs() < 0.02 * albedo.max(0.05),
+        "sampled albedo {} against {}",
+        estimate,
+        albedo
+    );
+}
@@ -0,0 +1,208 @@
+use crate::core::Color;
+use crate::objects::HitRecord;
+use crate::spectrum;
+use crate::textures::{SolidColor, Texture};
+use crate::utils::clamp;
+
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Sub};
+
+// Wavelengths in nm at which RGB-specified optical constants are taken to apply.
+const RGB_WAVELENGTHS: [f64; 3] = [630.0, 532.0, 465.0];
+
+// Samples used to integrate the reflectance spectrum when the ray has no wavelength.
+const RGB_STEPS: usize = 24;
+
+// A dielectric film a few hundred nanometres thick on top of a surface, whose
+// reflections interfere to give soap bubble and oil slick colours.
+#[derive(Clone)]
+pub struct ThinFilm {
+    thickness: Texture,
+    ior: f64,
+}
+
+impl ThinFilm {
+    pub fn new(thickness: f64, ior: f64) -> Self {
+        Self::new_textured(SolidColor::from(thickness), ior)
+    }
+
+    // `thickness` is read in nm.
+    pub fn new_textured(thickness: Texture, ior: f64) -> Self {
+        Self { thickness, ior }
+    }
+
+    // Reflectance for light arriving at `cos_theta_i` from a medium of index `outer`
+    // onto a substrate whose complex index at a wavelength is given by `substrate`.
+    // With no wavelength the reflectance spectrum is integrated to RGB.
+    pub fn reflectance<F>(
+        &self,
+        rec: &HitRecord,
+        cos_theta_i: f64,
+        outer: f64,
+        substrate: F,
+        wavelength: Option<f64>,
+    ) -> Color
+    where
+        F: Fn(f64) -> (f64, f64),
+    {
+        let thickness = self.thickness.scalar(rec.u, rec.v, rec.p).max(0.0);
+        let at = |lambda: f64| {
+            let (eta, k) = substrate(lambda);
+            airy(
+                cos_theta_i,
+                outer,
+                self.ior,
+                Complex::new(eta, k),
+                thickness,
+                lambda,
+            )
+        };
+
+        match wavelength {
+            Some(lambda) => Color::from(at(lambda)),
+            None => spectrum::integrate_rgb(at, RGB_STEPS),
+        }
+    }
+}
+
+// Value of an RGB-specified optical constant at `lambda`, interpolating between
+// the channels' representative wavelengths.
+pub fn rgb_at(color: Color, lambda: f64) -> f64 {
+    let [red, green, blue] = RGB_WAVELENGTHS;
+    if lambda >= red {
+        color.x()
+    } else if lambda >= green {
+        color.y() + (color.x() - color.y()) * (lambda - green) / (red - green)
+    } else if lambda >= blue {
+        color.z() + (color.y() - color.z()) * (lambda - blue) / (green - blue)
+    } else {
+        color.z()
+    }
+}
+
+// Airy summation of the multiple reflections inside the film, averaged over
+// both polarizations.
+fn airy(cos_theta_i: f64, n1: f64, n2: f64, n3: Complex, thickness: f64, lambda: f64) -> f64 {
+    let cos1 = Complex::new(clamp(cos_theta_i, 0.0, 1.0), 0.0);
+    let sin2_1 = Complex::new(1.0 - cos1.re * cos1.re, 0.0);
+    let n1 = Complex::new(n1, 0.0);
+    let n2 = Complex::new(n2, 0.0);
+    let one = Complex::new(1.0, 0.0);
+
+    let ratio = |n: Complex| (n1 / n) * (n1 / n);
+    let cos2 = (one - ratio(n2) * sin2_1).sqrt();
+    let cos3 = (one - ratio(n3) * sin2_1).sqrt();
+
+    let s = |na: Complex, ca: Complex, nb: Complex, cb: Complex| {
+        (na * ca - nb * cb) / (na * ca + nb * cb)
+    };
+    let p = |na: Complex, ca: Complex, nb: Complex, cb: Complex| {
+        (nb * ca - na * cb) / (nb * ca + na * cb)
+    };
+
+    // Round trip phase through the film.
+    let phase = (n2 * cos2 * (4.0 * PI * thickness / lambda)).exp_i();
+    let sum = |r12: Complex, r23: Complex| {
+        let r = (r12 + r23 * phase) / (one + r12 * r23 * phase);
+        r.norm_squared()
+    };
+
+    let rs = sum(s(n1, cos1, n2, cos2), s(n2, cos2, n3, cos3));
+    let rp = sum(p(n1, cos1, n2, cos2), p(n2, cos2, n3, cos3));
+    (0.5 * (rs + rp)).min(1.0)
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    fn norm_squared(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    fn sqrt(self) -> Self {
+        let norm = self.norm_squared().sqrt();
+        let re = ((norm + self.re) / 2.0).max(0.0).sqrt();
+        let im = ((norm - self.re) / 2.0).max(0.0).sqrt();
+        Self::new(re, if self.im < 0.0 { -im } else { im })
+    }
+
+    // e^(i z)
+    fn exp_i(self) -> Self {
+        let magnitude = (-self.im).exp();
+        Self::new(magnitude * self.re.cos(), magnitude * self.re.sin())
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Self;
+
+    fn mul(self, other: f64) -> Self {
+        Self::new(self.re * other, self.im * other)
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let denominator = other.norm_squared();
+        Self::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::check_bsdf;
+    use super::super::Conductor;
+    use super::ThinFilm;
+    use crate::core::{Color, Vector};
+
+    #[test]
+    fn rough_thin_film_conductor_is_sampled_by_its_pdf() {
+        let material = Conductor::new_thin_film_material(
+            Color::new(1.66, 0.88, 0.52),
+            Color::new(9.22, 6.27, 4.84),
+            0.5,
+            ThinFilm::new(350.0, 1.45),
+        );
+        check_bsdf(&material, Vector::new(1, 0, 1));
+    }
+}
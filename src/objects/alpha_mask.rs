@@ -0,0 +1,143 @@
+use super::{Aabb, HitRecord, Object};
+use crate::core::Ray;
+use crate::materials::Material;
+use crate::textures::Texture;
+
+use std::ops::Range;
+
+use rand::prelude::*;
+
+#[derive(Copy, Clone, Debug)]
+pub enum AlphaTest {
+    // Hits with opacity below the cutoff are skipped.
+    Threshold(f64),
+    // Hits are kept with probability equal to the opacity.
+    Stochastic,
+}
+
+// Cuts holes in `object` where an opacity texture says it is transparent. Skipped
+// hits are ignored entirely, so rays carry on to whatever lies behind them.
+pub struct AlphaMask<O> {
+    object: O,
+    opacity: Texture,
+    test: AlphaTest,
+}
+
+impl<O> AlphaMask<O> {
+    pub fn new(object: O, opacity: Texture, test: AlphaTest) -> Self {
+        Self {
+            object,
+            opacity,
+            test,
+        }
+    }
+}
+
+impl<O: Object> Object for AlphaMask<O> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
+        let mut t_min = t_range.start;
+        loop {
+            let rec = self.object.hit(ray, t_min..t_range.end, rng)?;
+            let opacity = self.opacity.scalar(rec.u, rec.v, rec.p);
+
+            let opaque = match self.test {
+                AlphaTest::Threshold(cutoff) => opacity >= cutoff,
+                AlphaTest::Stochastic => rng.gen::<f64>() < opacity,
+            };
+            if opaque {
+                return Some(rec);
+            }
+
+            // The same epsilon the integrators leave for self-intersection.
+            t_min = rec.t + 0.001;
+        }
+    }
+
+    fn bounding_box(&self, t_range: Range<f64>) -> Aabb {
+        self.object.bounding_box(t_range)
+    }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        self.object.materials(materials);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlphaMask, AlphaTest};
+    use crate::core::{Color, Point, Ray, Vector};
+    use crate::integrators::{Integrator, PathTracer};
+    use crate::lights::Light;
+    use crate::materials::Material;
+    use crate::objects::{Object, ObjectList, XZRect};
+    use crate::textures::SolidColor;
+
+    use std::f64::consts::PI;
+
+    fn lambertian() -> Material {
+        Material::Lambertian {
+            albedo: SolidColor::from(0.5),
+        }
+    }
+
+    // A floor at y = 0 under a masked sheet at y = `height`.
+    fn scene(height: f64, opacity: f64, test: AlphaTest) -> ObjectList {
+        let mut world = ObjectList::new();
+        world.add(Box::new(XZRect::new(
+            -100.0,
+            100.0,
+            -100.0,
+            100.0,
+            0.0,
+            lambertian(),
+        )));
+        let sheet = XZRect::new(-100.0, 100.0, -100.0, 100.0, height, lambertian());
+        world.add(Box::new(AlphaMask::new(
+            sheet,
+            SolidColor::from(opacity),
+            test,
+        )));
+        world
+    }
+
+    #[test]
+    fn rays_pass_cut_outs_to_what_lies_behind() {
+        let mut rng = rand::thread_rng();
+        let ray = Ray::new(Point::new(0, 2, 0), Vector::new(0, -1, 0), 0.0);
+
+        for &test in [AlphaTest::Threshold(0.5), AlphaTest::Stochastic].iter() {
+            let cut_out = scene(1.0, 0.0, test);
+            let rec = cut_out.hit(&ray, 0.001..f64::INFINITY, &mut rng).unwrap();
+            assert!((rec.t - 2.0).abs() < 1e-12);
+
+            let opaque = scene(1.0, 1.0, test);
+            let rec = opaque.hit(&ray, 0.001..f64::INFINITY, &mut rng).unwrap();
+            assert!((rec.t - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn shadow_rays_pass_cut_outs() {
+        let integrator = PathTracer::new(10, 3, false);
+        let lights = [Light::directional(Vector::new(0, -1, 0), Color::from(1))];
+        // Looking at the floor from below the sheet, so only shadow rays cross it.
+        let ray = Ray::new(Point::new(0, 1, 0), Vector::new(0, -1, 0), 0.0);
+        let radiance = |world: &ObjectList| {
+            integrator
+                .li(
+                    &ray,
+                    world,
+                    &lights,
+                    &Color::from(0),
+                    &mut rand::thread_rng(),
+                )
+                .y()
+        };
+
+        let lit = radiance(&scene(2.0, 0.0, AlphaTest::Threshold(0.5)));
+        assert!((lit - 0.5 / PI).abs() < 1e-9, "{}", lit);
+
+        let shadowed = radiance(&scene(2.0, 1.0, AlphaTest::Threshold(0.5)));
+        assert!(shadowed < lit, "{}", shadowed);
+    }
+}
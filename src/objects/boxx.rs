@@ -72,11 +72,15 @@ impl Boxx {
 }
 
 impl Object for Boxx {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
         self.sides.hit(ray, t_range, rng)
     }
 
     fn bounding_box(&self, _t_range: Range<f64>) -> Aabb {
         Aabb::new(self.min, self.max)
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        self.sides.materials(materials);
+    }
 }
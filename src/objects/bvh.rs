@@ -1,5 +1,6 @@
 use super::{Aabb, HitRecord, Object};
 use crate::core::Ray;
+use crate::materials::Material;
 
 use std::cmp::Ordering;
 use std::ops::Range;
@@ -13,39 +14,43 @@ pub struct Bvh {
 
 enum BvhContents {
     Node { left: Box<Bvh>, right: Box<Bvh> },
-    Leaf(Box<dyn Object>),
+    Leaf(usize, Box<dyn Object>),
 }
 
 impl Bvh {
-    pub fn new(
-        mut objects: Vec<Box<dyn Object>>,
+    pub fn new(objects: Vec<Box<dyn Object>>, t_range: Range<f64>, rng: &mut ThreadRng) -> Self {
+        // Leaves are numbered from 1, leaving 0 for hits outside the hierarchy.
+        let objects = objects.into_iter().enumerate().map(|(i, o)| (i + 1, o));
+        Self::build(objects.collect(), t_range, rng)
+    }
+
+    fn build(
+        mut objects: Vec<(usize, Box<dyn Object>)>,
         t_range: Range<f64>,
         rng: &mut ThreadRng,
     ) -> Self {
         use BvhContents::*;
 
         let axis = rng.gen_range(0, 3);
-        let comparator = match axis {
-            0 => box_x_compare,
-            1 => box_y_compare,
-            _ => box_z_compare,
-        };
 
         let object_span = objects.len();
 
         match object_span {
-            1 => Bvh {
-                bounds: objects[0].bounding_box(t_range),
-                contents: Leaf(objects.pop().unwrap()),
-            },
+            1 => {
+                let (id, object) = objects.pop().unwrap();
+                Bvh {
+                    bounds: object.bounding_box(t_range),
+                    contents: Leaf(id, object),
+                }
+            }
             _ => {
-                objects.sort_by(comparator);
-                let right = Self::new(
+                objects.sort_by(|(_, a), (_, b)| box_compare(a.as_ref(), b.as_ref(), axis));
+                let right = Self::build(
                     objects.drain(object_span / 2..).collect(),
                     t_range.clone(),
                     rng,
                 );
-                let left = Self::new(objects, t_range.clone(), rng);
+                let left = Self::build(objects, t_range.clone(), rng);
                 let bounds = Aabb::surrounding_box(
                     &left.bounding_box(t_range.clone()),
                     &right.bounding_box(t_range),
@@ -63,18 +68,42 @@ impl Bvh {
     }
 }
 
+impl Bvh {
+    fn leaves<'o>(&'o self, leaves: &mut Vec<(usize, &'o dyn Object)>) {
+        match &self.contents {
+            BvhContents::Node { left, right } => {
+                left.leaves(leaves);
+                right.leaves(leaves);
+            }
+            BvhContents::Leaf(id, object) => leaves.push((*id, object.as_ref())),
+        }
+    }
+}
+
 impl Object for Bvh {
-    fn hit(&self, ray: &Ray, mut t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
+        self.hit_counting_nodes(ray, t_range, rng, &mut 0)
+    }
+
+    fn hit_counting_nodes(
+        &self,
+        ray: &Ray,
+        mut t_range: Range<f64>,
+        rng: &mut ThreadRng,
+        nodes: &mut usize,
+    ) -> Option<HitRecord<'_>> {
+        *nodes += 1;
+
         if self.bounds.hit(ray, t_range.start, t_range.end) {
             match &self.contents {
                 BvhContents::Node { left, right } => {
-                    let hit_left = left.hit(ray, t_range.clone(), rng);
+                    let hit_left = left.hit_counting_nodes(ray, t_range.clone(), rng, nodes);
 
                     if let Some(ref hl) = hit_left {
                         t_range.end = hl.t;
                     }
 
-                    let hit_right = right.hit(ray, t_range, rng);
+                    let hit_right = right.hit_counting_nodes(ray, t_range, rng, nodes);
 
                     match (hit_left, hit_right) {
                         (h, None) | (None, h) => h,
@@ -87,7 +116,13 @@ impl Object for Bvh {
                         }
                     }
                 }
-                BvhContents::Leaf(obj) => obj.hit(ray, t_range, rng),
+                BvhContents::Leaf(id, obj) => {
+                    obj.hit_counting_nodes(ray, t_range, rng, nodes)
+                        .map(|mut rec| {
+                            rec.object_id = *id;
+                            rec
+                        })
+                }
             }
         } else {
             None
@@ -97,23 +132,21 @@ impl Object for Bvh {
     fn bounding_box(&self, _t_range: Range<f64>) -> Aabb {
         self.bounds.clone()
     }
+
+    // The tree's shape is random, so leaves are listed in the order they were given.
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        let mut leaves = Vec::new();
+        self.leaves(&mut leaves);
+        leaves.sort_by_key(|&(id, _)| id);
+        for (_, object) in leaves {
+            object.materials(materials);
+        }
+    }
 }
 
-fn box_compare(a: &Box<dyn Object>, b: &Box<dyn Object>, axis: usize) -> Ordering {
+fn box_compare(a: &dyn Object, b: &dyn Object, axis: usize) -> Ordering {
     let box_a = a.bounding_box(0.0..0.0);
     let box_b = b.bounding_box(0.0..0.0);
 
     box_a.min[axis].partial_cmp(&box_b.min[axis]).unwrap()
 }
-
-fn box_x_compare(a: &Box<dyn Object>, b: &Box<dyn Object>) -> Ordering {
-    box_compare(a, b, 0)
-}
-
-fn box_y_compare(a: &Box<dyn Object>, b: &Box<dyn Object>) -> Ordering {
-    box_compare(a, b, 1)
-}
-
-fn box_z_compare(a: &Box<dyn Object>, b: &Box<dyn Object>) -> Ordering {
-    box_compare(a, b, 2)
-}
@@ -1,9 +1,8 @@
-use super::{Aabb, HitRecord, Object};
+use super::{Aabb, HitRecord, Object, ToWorld};
 use crate::core::{Ray, Vector};
 use crate::materials::Material;
 use crate::textures::Texture;
 
-use std::f64::{INFINITY, NEG_INFINITY};
 use std::ops::Range;
 
 use rand::prelude::*;
@@ -11,23 +10,35 @@ use rand::prelude::*;
 pub struct ConstantMedium<O> {
     boundary: O,
     phase_function: Material,
-    neg_inv_density: f64,
+    density: f64,
 }
 
 impl<O> ConstantMedium<O> {
     pub fn new(boundary: O, density: f64, texture: Texture) -> Self {
         Self {
             boundary,
-            neg_inv_density: -1.0 / density,
+            density,
             phase_function: Material::Isotropic { albedo: texture },
         }
     }
 }
 
+// Distance travelled through a homogeneous medium before the next collision,
+// drawn from the exponential distribution with rate `density`.
+pub fn free_flight_distance(density: f64, rng: &mut ThreadRng) -> f64 {
+    -(1.0 - rng.gen::<f64>()).ln() / density
+}
+
 impl<O: Object> Object for ConstantMedium<O> {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord> {
-        if let Some(mut rec1) = self.boundary.hit(ray, NEG_INFINITY..INFINITY, rng) {
-            if let Some(mut rec2) = self.boundary.hit(ray, (rec1.t + 0.0001)..INFINITY, rng) {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
+        if let Some(mut rec1) = self
+            .boundary
+            .hit(ray, f64::NEG_INFINITY..f64::INFINITY, rng)
+        {
+            if let Some(mut rec2) = self
+                .boundary
+                .hit(ray, (rec1.t + 0.0001)..f64::INFINITY, rng)
+            {
                 if rec1.t < t_range.start {
                     rec1.t = t_range.start;
                 }
@@ -45,7 +56,7 @@ impl<O: Object> Object for ConstantMedium<O> {
 
                 let ray_length = ray.direction.length();
                 let distance_inside_boundary = (rec2.t - rec1.t) * ray_length;
-                let hit_distance = self.neg_inv_density * rng.gen::<f64>().log2();
+                let hit_distance = free_flight_distance(self.density, rng);
 
                 if hit_distance > distance_inside_boundary {
                     return None;
@@ -63,7 +74,11 @@ impl<O: Object> Object for ConstantMedium<O> {
                     v: 0.0,
                     p,
                     normal,
+                    dpdu: Vector::new(1, 0, 0),
+                    dpdv: Vector::new(0, 1, 0),
                     front_face,
+                    object_id: 0,
+                    to_world: ToWorld::identity(),
                     material,
                 });
             }
@@ -75,4 +90,8 @@ impl<O: Object> Object for ConstantMedium<O> {
     fn bounding_box(&self, t_range: Range<f64>) -> Aabb {
         self.boundary.bounding_box(t_range)
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        materials.push(&self.phase_function);
+    }
 }
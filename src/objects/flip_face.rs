@@ -1,5 +1,6 @@
 use super::{Aabb, HitRecord, Object};
 use crate::core::Ray;
+use crate::materials::Material;
 
 use std::ops::Range;
 
@@ -16,8 +17,18 @@ impl<O> FlipFace<O> {
 }
 
 impl<O: Object> Object for FlipFace<O> {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord> {
-        if let Some(mut rec) = self.object.hit(ray, t_range, rng) {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
+        self.hit_counting_nodes(ray, t_range, rng, &mut 0)
+    }
+
+    fn hit_counting_nodes(
+        &self,
+        ray: &Ray,
+        t_range: Range<f64>,
+        rng: &mut ThreadRng,
+        nodes: &mut usize,
+    ) -> Option<HitRecord<'_>> {
+        if let Some(mut rec) = self.object.hit_counting_nodes(ray, t_range, rng, nodes) {
             rec.front_face = !rec.front_face;
             Some(rec)
         } else {
@@ -28,4 +39,8 @@ impl<O: Object> Object for FlipFace<O> {
     fn bounding_box(&self, t_range: Range<f64>) -> Aabb {
         self.object.bounding_box(t_range)
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        self.object.materials(materials);
+    }
 }
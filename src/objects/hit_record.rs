@@ -1,14 +1,81 @@
 use crate::core::{Point, Ray, Vector};
 use crate::materials::Material;
 
+#[derive(Copy, Clone)]
 pub struct HitRecord<'m> {
     pub p: Point,
     pub normal: Vector,
+    // Surface tangents along increasing u and v, unnormalized.
+    pub dpdu: Vector,
+    pub dpdv: Vector,
     pub material: &'m Material,
     pub t: f64,
     pub u: f64,
     pub v: f64,
     pub front_face: bool,
+    // BVH leaf that was hit, numbered from 1; 0 if the hit came from outside a BVH.
+    pub object_id: usize,
+    // Placement of the primitive's own space in the world, built up by the
+    // `Translate` and `RotateY` wrappers the hit passed through.
+    pub to_world: ToWorld,
+}
+
+// Rigid motion made of a rotation about the y axis followed by an offset.
+#[derive(Copy, Clone, Debug)]
+pub struct ToWorld {
+    sin_theta: f64,
+    cos_theta: f64,
+    offset: Vector,
+}
+
+impl ToWorld {
+    pub fn identity() -> Self {
+        Self {
+            sin_theta: 0.0,
+            cos_theta: 1.0,
+            offset: Vector::from(0),
+        }
+    }
+
+    pub(super) fn translated(self, offset: Vector) -> Self {
+        Self {
+            offset: self.offset + offset,
+            ..self
+        }
+    }
+
+    pub(super) fn rotated_y(self, sin_theta: f64, cos_theta: f64) -> Self {
+        Self {
+            sin_theta: sin_theta * self.cos_theta + cos_theta * self.sin_theta,
+            cos_theta: cos_theta * self.cos_theta - sin_theta * self.sin_theta,
+            offset: rotate_y(self.offset, sin_theta, cos_theta),
+        }
+    }
+
+    pub fn point(&self, p: Point) -> Point {
+        self.vector(p) + self.offset
+    }
+
+    pub fn vector(&self, v: Vector) -> Vector {
+        rotate_y(v, self.sin_theta, self.cos_theta)
+    }
+
+    pub fn inverse_point(&self, p: Point) -> Point {
+        self.inverse_vector(p - self.offset)
+    }
+
+    pub fn inverse_vector(&self, v: Vector) -> Vector {
+        rotate_y(v, -self.sin_theta, self.cos_theta)
+    }
+}
+
+// The rotation `RotateY` applies to hits on the way out.
+pub(super) fn rotate_y(a: Vector, sin_theta: f64, cos_theta: f64) -> Vector {
+    Vector::new(
+        cos_theta * a.x() + sin_theta * a.z(),
+        a.y(),
+        -sin_theta * a.x() + cos_theta * a.z(),
+    )
 }
 
 impl<'m> HitRecord<'m> {
@@ -20,4 +87,14 @@ impl<'m> HitRecord<'m> {
             -outward_normal
         };
     }
+
+    // A copy shaded with a perturbed normal. Normals that would face away from
+    // the incoming ray fall back to the geometric one.
+    pub fn with_shading_normal(&self, ray: &Ray, normal: Vector) -> Self {
+        let mut rec = *self;
+        if normal.dot(ray.direction) < 0.0 {
+            rec.normal = normal;
+        }
+        rec
+    }
 }
@@ -1,4 +1,5 @@
 mod aabb;
+mod alpha_mask;
 mod boxx;
 mod bvh;
 mod constant_medium;
@@ -8,23 +9,27 @@ mod moving_sphere;
 mod object_list;
 mod rotate_y;
 mod sphere;
+mod subsurface_medium;
 mod translate;
 mod xy_rect;
 mod xz_rect;
 mod yz_rect;
 
 use crate::core::Ray;
+use crate::materials::Material;
 
 pub use aabb::Aabb;
+pub use alpha_mask::{AlphaMask, AlphaTest};
 pub use boxx::Boxx;
 pub use bvh::Bvh;
-pub use constant_medium::ConstantMedium;
+pub use constant_medium::{free_flight_distance, ConstantMedium};
 pub use flip_face::FlipFace;
-pub use hit_record::HitRecord;
+pub use hit_record::{HitRecord, ToWorld};
 pub use moving_sphere::MovingSphere;
 pub use object_list::ObjectList;
 pub use rotate_y::RotateY;
 pub use sphere::Sphere;
+pub use subsurface_medium::SubsurfaceMedium;
 pub use translate::Translate;
 pub use xy_rect::XYRect;
 pub use xz_rect::XZRect;
@@ -42,5 +47,21 @@ pub trait Object: Send + Sync {
         rng: &mut ThreadRng,
     ) -> Option<HitRecord<'o>>;
 
+    // `hit`, also adding to `nodes` the hierarchy nodes the query visits, for
+    // debug views of how much work it does. Objects without one visit none.
+    fn hit_counting_nodes<'o>(
+        &'o self,
+        ray: &Ray,
+        t_range: Range<f64>,
+        rng: &mut ThreadRng,
+        _nodes: &mut usize,
+    ) -> Option<HitRecord<'o>> {
+        self.hit(ray, t_range, rng)
+    }
+
     fn bounding_box(&self, t_range: Range<f64>) -> Aabb;
+
+    // Pushes every material the object's hits can report, in an order that only
+    // depends on how the scene was described, so they can be numbered.
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>);
 }
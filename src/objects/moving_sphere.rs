@@ -1,4 +1,4 @@
-use super::{Aabb, HitRecord, Object};
+use super::{Aabb, HitRecord, Object, Sphere, ToWorld};
 use crate::core::{Point, Ray, Vector};
 use crate::materials::Material;
 
@@ -42,7 +42,7 @@ impl MovingSphere {
 }
 
 impl Object for MovingSphere {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
         let oc = ray.origin - self.center(ray.time);
         let a = ray.direction.length_squared();
         let half_b = oc.dot(ray.direction);
@@ -58,16 +58,23 @@ impl Object for MovingSphere {
                 let t = temp;
                 let p = ray.at(t);
                 let outward_normal = (p - self.center(ray.time)) / self.radius;
+                let (mut u, mut v) = (0.0, 0.0);
+                Sphere::get_sphere_uv(outward_normal, &mut u, &mut v);
+                let (dpdu, dpdv) = Sphere::get_sphere_tangents(outward_normal, self.radius);
                 let material = &self.material;
 
                 let mut hit_rec = HitRecord {
                     t,
-                    u: 0.0,
-                    v: 0.0,
+                    u,
+                    v,
                     p,
                     normal: outward_normal,
+                    dpdu,
+                    dpdv,
                     material,
                     front_face: true,
+                    object_id: 0,
+                    to_world: ToWorld::identity(),
                 };
 
                 hit_rec.set_face_normal(ray, outward_normal);
@@ -79,16 +86,23 @@ impl Object for MovingSphere {
                 let t = temp;
                 let p = ray.at(t);
                 let outward_normal = (p - self.center(ray.time)) / self.radius;
+                let (mut u, mut v) = (0.0, 0.0);
+                Sphere::get_sphere_uv(outward_normal, &mut u, &mut v);
+                let (dpdu, dpdv) = Sphere::get_sphere_tangents(outward_normal, self.radius);
                 let material = &self.material;
 
                 let mut hit_rec = HitRecord {
                     t,
-                    u: 0.0,
-                    v: 0.0,
+                    u,
+                    v,
                     p,
                     normal: outward_normal,
+                    dpdu,
+                    dpdv,
                     material,
                     front_face: true,
+                    object_id: 0,
+                    to_world: ToWorld::identity(),
                 };
 
                 hit_rec.set_face_normal(ray, outward_normal);
@@ -112,4 +126,8 @@ impl Object for MovingSphere {
 
         Aabb::surrounding_box(&box0, &box1)
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        materials.push(&self.material);
+    }
 }
@@ -1,5 +1,6 @@
 use super::{Aabb, HitRecord, Object};
 use crate::core::{Point, Ray};
+use crate::materials::Material;
 
 use std::ops::Range;
 
@@ -23,7 +24,7 @@ impl ObjectList {
 }
 
 impl Object for ObjectList {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
         let mut rec = None;
         let mut closest_so_far = t_range.end;
 
@@ -45,4 +46,10 @@ impl Object for ObjectList {
                 Aabb::surrounding_box(&acc, &bounding_box)
             })
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        for object in self.objects.iter() {
+            object.materials(materials);
+        }
+    }
 }
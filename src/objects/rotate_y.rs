@@ -1,8 +1,9 @@
+use super::hit_record::rotate_y;
 use super::{Aabb, HitRecord, Object};
 use crate::core::{Point, Ray, Vector};
+use crate::materials::Material;
 use crate::utils::degrees_to_radians;
 
-use std::f64::INFINITY;
 use std::ops::Range;
 
 use rand::prelude::*;
@@ -21,8 +22,8 @@ impl<O: Object> RotateY<O> {
         let cos_theta = radians.cos();
         let mut bbox = object.bounding_box(0.0..1.0);
 
-        let mut min = Point::from(INFINITY);
-        let mut max = Point::from(-INFINITY);
+        let mut min = Point::from(f64::INFINITY);
+        let mut max = Point::from(-f64::INFINITY);
 
         for i in 0..2 {
             for j in 0..2 {
@@ -60,7 +61,17 @@ impl<O: Object> RotateY<O> {
 }
 
 impl<O: Object> Object for RotateY<O> {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
+        self.hit_counting_nodes(ray, t_range, rng, &mut 0)
+    }
+
+    fn hit_counting_nodes(
+        &self,
+        ray: &Ray,
+        t_range: Range<f64>,
+        rng: &mut ThreadRng,
+        nodes: &mut usize,
+    ) -> Option<HitRecord<'_>> {
         let mut origin = ray.origin;
         let mut direction = ray.direction;
 
@@ -72,18 +83,17 @@ impl<O: Object> Object for RotateY<O> {
 
         let rotated_ray = Ray::new(origin, direction, ray.time);
 
-        if let Some(mut rec) = self.object.hit(&rotated_ray, t_range, rng) {
-            let mut p = rec.p;
-            let mut normal = rec.normal;
-
-            p[0] = self.cos_theta * rec.p[0] + self.sin_theta * rec.p[2];
-            p[2] = -self.sin_theta * rec.p[0] + self.cos_theta * rec.p[2];
+        if let Some(mut rec) = self
+            .object
+            .hit_counting_nodes(&rotated_ray, t_range, rng, nodes)
+        {
+            let rotate = |a: Vector| rotate_y(a, self.sin_theta, self.cos_theta);
 
-            normal[0] = self.cos_theta * rec.normal[0] + self.sin_theta * rec.normal[2];
-            normal[2] = -self.sin_theta * rec.normal[0] + self.cos_theta * rec.normal[2];
-
-            rec.p = p;
-            rec.set_face_normal(&rotated_ray, normal);
+            rec.p = rotate(rec.p);
+            rec.dpdu = rotate(rec.dpdu);
+            rec.dpdv = rotate(rec.dpdv);
+            rec.set_face_normal(&rotated_ray, rotate(rec.normal));
+            rec.to_world = rec.to_world.rotated_y(self.sin_theta, self.cos_theta);
 
             Some(rec)
         } else {
@@ -94,4 +104,8 @@ impl<O: Object> Object for RotateY<O> {
     fn bounding_box(&self, _t_range: Range<f64>) -> Aabb {
         self.bbox.clone()
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        self.object.materials(materials);
+    }
 }
@@ -1,6 +1,7 @@
-use super::{Aabb, HitRecord, Object};
+use super::{Aabb, HitRecord, Object, ToWorld};
 use crate::core::{Point, Ray, Vector};
 use crate::materials::Material;
+use crate::utils::clamp;
 use std::f64::consts::PI;
 
 use std::ops::Range;
@@ -23,16 +24,25 @@ impl Sphere {
         }
     }
 
-    fn get_sphere_uv(p: Point, u: &mut f64, v: &mut f64) {
+    pub(crate) fn get_sphere_uv(p: Point, u: &mut f64, v: &mut f64) {
         let phi = p.z().atan2(p.x());
-        let theta = p.y().asin();
+        // Rounding can leave a unit vector's y just outside [-1, 1].
+        let theta = clamp(p.y(), -1.0, 1.0).asin();
         *u = 1.0 - (phi + PI) / (2.0 * PI);
         *v = (theta + PI / 2.0) / PI;
     }
+
+    // Derivatives of the `get_sphere_uv` parameterization; dpdu vanishes at the poles.
+    pub(super) fn get_sphere_tangents(p: Point, radius: f64) -> (Vector, Vector) {
+        let rho = (p.x() * p.x() + p.z() * p.z()).sqrt().max(1e-9);
+        let dpdu = Vector::new(p.z(), 0.0, -p.x()) * (2.0 * PI * radius);
+        let dpdv = Vector::new(-p.y() * p.x() / rho, rho, -p.y() * p.z() / rho) * (PI * radius);
+        (dpdu, dpdv)
+    }
 }
 
 impl Object for Sphere {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
         let oc = ray.origin - self.center;
         let a = ray.direction.length_squared();
         let half_b = oc.dot(ray.direction);
@@ -47,7 +57,8 @@ impl Object for Sphere {
                 let p = ray.at(t);
                 let outward_normal = (p - self.center) / self.radius;
                 let (mut u, mut v) = (0.0, 0.0);
-                Self::get_sphere_uv((p - self.center) / self.radius, &mut u, &mut v);
+                Self::get_sphere_uv(outward_normal, &mut u, &mut v);
+                let (dpdu, dpdv) = Self::get_sphere_tangents(outward_normal, self.radius);
                 let material = &self.material;
                 let mut hit_rec = HitRecord {
                     t,
@@ -55,8 +66,12 @@ impl Object for Sphere {
                     v,
                     p,
                     normal: outward_normal,
+                    dpdu,
+                    dpdv,
                     material,
                     front_face: true,
+                    object_id: 0,
+                    to_world: ToWorld::identity(),
                 };
 
                 hit_rec.set_face_normal(ray, outward_normal);
@@ -68,7 +83,8 @@ impl Object for Sphere {
                 let p = ray.at(t);
                 let outward_normal = (p - self.center) / self.radius;
                 let (mut u, mut v) = (0.0, 0.0);
-                Self::get_sphere_uv((p - self.center) / self.radius, &mut u, &mut v);
+                Self::get_sphere_uv(outward_normal, &mut u, &mut v);
+                let (dpdu, dpdv) = Self::get_sphere_tangents(outward_normal, self.radius);
                 let material = &self.material;
                 let mut hit_rec = HitRecord {
                     t,
@@ -76,8 +92,12 @@ impl Object for Sphere {
                     v,
                     p,
                     normal: outward_normal,
+                    dpdu,
+                    dpdv,
                     material,
                     front_face: true,
+                    object_id: 0,
+                    to_world: ToWorld::identity(),
                 };
 
                 hit_rec.set_face_normal(ray, outward_normal);
@@ -94,4 +114,40 @@ impl Object for Sphere {
             self.center + Vector::from(self.radius),
         )
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        materials.push(&self.material);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sphere;
+    use crate::core::Point;
+
+    use std::f64::consts::PI;
+
+    #[test]
+    fn sphere_tangents_lie_in_the_surface() {
+        let radius = 2.0;
+        for i in 1..20 {
+            for j in 0..40 {
+                let theta = PI * i as f64 / 20.0;
+                let phi = PI * j as f64 / 20.0;
+                let p = Point::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+
+                let (dpdu, dpdv) = Sphere::get_sphere_tangents(p, radius);
+                let scale = dpdu.length() * dpdv.length();
+                assert!(dpdu.dot(p).abs() < 1e-12 * scale.max(1.0));
+                assert!(dpdv.dot(p).abs() < 1e-12 * scale.max(1.0));
+                assert!(dpdu.dot(dpdv).abs() < 1e-9 * scale);
+                // Together they face outwards, like the sphere's normal.
+                assert!(dpdu.cross(dpdv).dot(p) > 0.0);
+            }
+        }
+    }
 }
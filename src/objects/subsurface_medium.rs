@@ -0,0 +1,49 @@
+use super::{Aabb, HitRecord, Object, ToWorld};
+use crate::core::{Color, Ray};
+use crate::materials::{Material, Subsurface};
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use rand::prelude::*;
+
+// A closed object filled with a scattering medium that light enters and leaves
+// through its surface, e.g. skin, wax or marble.
+pub struct SubsurfaceMedium {
+    boundary: Arc<dyn Object>,
+    material: Material,
+}
+
+impl SubsurfaceMedium {
+    pub fn new<O: Object + 'static>(
+        boundary: O,
+        ref_index: f64,
+        albedo: Color,
+        mean_free_path: Color,
+    ) -> Self {
+        let boundary: Arc<dyn Object> = Arc::new(boundary);
+        let material =
+            Subsurface::new_material(boundary.clone(), ref_index, albedo, mean_free_path);
+
+        Self { boundary, material }
+    }
+}
+
+impl Object for SubsurfaceMedium {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
+        let mut rec = self.boundary.hit(ray, t_range, rng)?;
+        rec.material = &self.material;
+        // Wrappers inside the boundary are part of its shape; the walk starts
+        // from the boundary as a whole.
+        rec.to_world = ToWorld::identity();
+        Some(rec)
+    }
+
+    fn bounding_box(&self, t_range: Range<f64>) -> Aabb {
+        self.boundary.bounding_box(t_range)
+    }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        materials.push(&self.material);
+    }
+}
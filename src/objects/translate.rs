@@ -1,5 +1,6 @@
 use super::{Aabb, HitRecord, Object};
 use crate::core::{Ray, Vector};
+use crate::materials::Material;
 
 use std::ops::Range;
 
@@ -17,11 +18,25 @@ impl<O> Translate<O> {
 }
 
 impl<O: Object> Object for Translate<O> {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
+        self.hit_counting_nodes(ray, t_range, rng, &mut 0)
+    }
+
+    fn hit_counting_nodes(
+        &self,
+        ray: &Ray,
+        t_range: Range<f64>,
+        rng: &mut ThreadRng,
+        nodes: &mut usize,
+    ) -> Option<HitRecord<'_>> {
         let moved_ray = Ray::new(ray.origin - self.offset, ray.direction, ray.time);
 
-        if let Some(mut rec) = self.object.hit(&moved_ray, t_range, rng) {
+        if let Some(mut rec) = self
+            .object
+            .hit_counting_nodes(&moved_ray, t_range, rng, nodes)
+        {
             rec.p += self.offset;
+            rec.to_world = rec.to_world.translated(self.offset);
             rec.set_face_normal(&moved_ray, rec.normal);
             Some(rec)
         } else {
@@ -34,4 +49,8 @@ impl<O: Object> Object for Translate<O> {
 
         Aabb::new(output_box.min + self.offset, output_box.max + self.offset)
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        self.object.materials(materials);
+    }
 }
@@ -1,4 +1,4 @@
-use super::{Aabb, HitRecord, Object};
+use super::{Aabb, HitRecord, Object, ToWorld};
 use crate::core::{Point, Ray, Vector};
 use crate::materials::Material;
 
@@ -29,7 +29,7 @@ impl XYRect {
 }
 
 impl Object for XYRect {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
         let t = (self.k - ray.origin.z()) / ray.direction.z();
         if t < t_range.start || t > t_range.end {
             return None;
@@ -53,8 +53,12 @@ impl Object for XYRect {
             v,
             p,
             normal: outward_normal,
+            dpdu: Vector::new(self.x1 - self.x0, 0, 0),
+            dpdv: Vector::new(0, self.y1 - self.y0, 0),
             material,
             front_face: true,
+            object_id: 0,
+            to_world: ToWorld::identity(),
         };
 
         hit_rec.set_face_normal(ray, outward_normal);
@@ -67,4 +71,8 @@ impl Object for XYRect {
             Point::new(self.x1, self.y1, self.k + 0.0001),
         )
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        materials.push(&self.material);
+    }
 }
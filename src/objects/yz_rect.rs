@@ -1,4 +1,4 @@
-use super::{Aabb, HitRecord, Object};
+use super::{Aabb, HitRecord, Object, ToWorld};
 use crate::core::{Point, Ray, Vector};
 use crate::materials::Material;
 
@@ -29,7 +29,7 @@ impl YZRect {
 }
 
 impl Object for YZRect {
-    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_range: Range<f64>, _rng: &mut ThreadRng) -> Option<HitRecord<'_>> {
         let t = (self.k - ray.origin.x()) / ray.direction.x();
         if t < t_range.start || t > t_range.end {
             return None;
@@ -43,7 +43,6 @@ impl Object for YZRect {
 
         let u = (y - self.y0) / (self.y1 - self.y0);
         let v = (z - self.z0) / (self.z1 - self.z0);
-        let t = t;
         let outward_normal = Vector::new(1, 0, 0);
         let material = &self.material;
         let p = ray.at(t);
@@ -54,8 +53,12 @@ impl Object for YZRect {
             v,
             p,
             normal: outward_normal,
+            dpdu: Vector::new(0, self.y1 - self.y0, 0),
+            dpdv: Vector::new(0, 0, self.z1 - self.z0),
             material,
             front_face: true,
+            object_id: 0,
+            to_world: ToWorld::identity(),
         };
 
         hit_rec.set_face_normal(ray, outward_normal);
@@ -69,4 +72,8 @@ impl Object for YZRect {
             Point::new(self.k + 0.0001, self.y1, self.z1),
         )
     }
+
+    fn materials<'o>(&'o self, materials: &mut Vec<&'o Material>) {
+        materials.push(&self.material);
+    }
 }
@@ -0,0 +1,130 @@
+use crate::core::{Color, Ray};
+use crate::integrators::Radiance;
+use crate::materials::Material;
+use crate::objects::{HitRecord, Object};
+
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Aov {
+    Albedo,
+    Depth,
+    Direct,
+    Emission,
+    Indirect,
+    MaterialId,
+    Normal,
+    ObjectId,
+    Position,
+}
+
+impl Aov {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Albedo => "albedo",
+            Aov::Depth => "depth",
+            Aov::Direct => "direct",
+            Aov::Emission => "emission",
+            Aov::Indirect => "indirect",
+            Aov::MaterialId => "material_id",
+            Aov::Normal => "normal",
+            Aov::ObjectId => "object_id",
+            Aov::Position => "position",
+        }
+    }
+
+    pub fn is_lighting(&self) -> bool {
+        matches!(self, Aov::Direct | Aov::Emission | Aov::Indirect)
+    }
+
+    // IDs can't be blended between samples, so only the first sample of a pixel is
+    // kept, and pixels where it hits nothing stay 0.
+    pub fn is_id(&self) -> bool {
+        matches!(self, Aov::MaterialId | Aov::ObjectId)
+    }
+
+    pub fn lighting(&self, radiance: &Radiance) -> Color {
+        match self {
+            Aov::Direct => radiance.direct,
+            Aov::Emission => radiance.emission,
+            Aov::Indirect => radiance.indirect,
+            _ => Color::from(0),
+        }
+    }
+
+    pub fn first_hit(&self, ray: &Ray, rec: &HitRecord, material_ids: &MaterialIds) -> Color {
+        match self {
+            Aov::Albedo => rec.material.albedo(rec),
+            Aov::Depth => Color::from(rec.t * ray.direction.length()),
+            Aov::MaterialId => Color::from(material_ids.get(rec.material) as f64),
+            Aov::Normal => rec.normal,
+            Aov::ObjectId => Color::from(rec.object_id as f64),
+            Aov::Position => rec.p,
+            _ => Color::from(0),
+        }
+    }
+}
+
+// Numbers the world's materials from 1 in the order its objects list them, so
+// ids match between runs. Distinct materials get distinct ids even if their
+// parameters match.
+pub struct MaterialIds {
+    // Keyed by address, which is only stable within a run.
+    ids: HashMap<usize, usize>,
+}
+
+impl MaterialIds {
+    pub fn new(world: &dyn Object) -> Self {
+        let mut materials = Vec::new();
+        world.materials(&mut materials);
+
+        let mut ids = HashMap::new();
+        for material in materials {
+            let next = ids.len() + 1;
+            ids.entry(address(material)).or_insert(next);
+        }
+        Self { ids }
+    }
+
+    // 0 for materials the world didn't list.
+    pub fn get(&self, material: &Material) -> usize {
+        self.ids.get(&address(material)).copied().unwrap_or(0)
+    }
+}
+
+fn address(material: &Material) -> usize {
+    material as *const Material as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MaterialIds;
+    use crate::core::{Point, Ray, Vector};
+    use crate::materials::Material;
+    use crate::objects::{Bvh, Object, Sphere};
+    use crate::textures::SolidColor;
+
+    #[test]
+    fn material_ids_follow_the_scene_order_whatever_the_bvh_shape() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..10 {
+            let spheres: Vec<Box<dyn Object>> = (0..8)
+                .map(|i| {
+                    let material = Material::Lambertian {
+                        albedo: SolidColor::from(0.5),
+                    };
+                    Box::new(Sphere::new(Point::new(3 * i, 0, 0), 1.0, material)) as Box<dyn Object>
+                })
+                .collect();
+            let world = Bvh::new(spheres, 0.0..1.0, &mut rng);
+            let ids = MaterialIds::new(&world);
+
+            for i in 0..8 {
+                let ray = Ray::new(Point::new(3 * i, 0, -5), Vector::new(0, 0, 1), 0.0);
+                let rec = world.hit(&ray, 0.001..f64::INFINITY, &mut rng).unwrap();
+                assert_eq!(ids.get(rec.material), i as usize + 1);
+            }
+        }
+    }
+}
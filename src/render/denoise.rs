@@ -0,0 +1,225 @@
+use super::Framebuffer;
+use crate::core::Color;
+
+use rayon::prelude::*;
+
+const KERNEL: [f64; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+// Edge-avoiding à-trous wavelet filter (Dammertz et al. 2010) guided by first-hit features.
+#[derive(Copy, Clone, Debug)]
+pub struct Denoiser {
+    pub iterations: u32,
+    pub sigma_color: f64,
+    pub sigma_normal: f64,
+    pub sigma_depth: f64,
+    pub sigma_albedo: f64,
+}
+
+pub struct Features<'f> {
+    pub albedo: &'f Framebuffer,
+    pub normal: &'f Framebuffer,
+    pub depth: &'f Framebuffer,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Self {
+            iterations: 5,
+            sigma_color: 0.5,
+            sigma_normal: 0.1,
+            sigma_depth: 0.05,
+            sigma_albedo: 0.1,
+        }
+    }
+}
+
+impl Denoiser {
+    pub fn denoise(&self, beauty: &Framebuffer, features: &Features) -> Framebuffer {
+        let (width, height) = (beauty.width(), beauty.height());
+
+        // Filter irradiance rather than radiance so texture detail isn't blurred away.
+        let mut irradiance = Framebuffer::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let albedo = demodulation_albedo(features.albedo.get(x, y));
+                irradiance.set(x, y, beauty.get(x, y) / albedo);
+            }
+        }
+
+        let mut sigma_color = self.sigma_color;
+        for i in 0..self.iterations {
+            irradiance = self.filter_pass(&irradiance, features, 1 << i, sigma_color);
+            sigma_color *= 0.5;
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let albedo = demodulation_albedo(features.albedo.get(x, y));
+                irradiance.set(x, y, irradiance.get(x, y) * albedo);
+            }
+        }
+
+        irradiance
+    }
+
+    fn filter_pass(
+        &self,
+        input: &Framebuffer,
+        features: &Features,
+        step: usize,
+        sigma_color: f64,
+    ) -> Framebuffer {
+        let (width, height) = (input.width(), input.height());
+
+        let rows: Vec<Vec<Color>> = (0..height)
+            .into_par_iter()
+            .map(|y| {
+                (0..width)
+                    .map(|x| self.filter_pixel(input, features, x, y, step, sigma_color))
+                    .collect()
+            })
+            .collect();
+
+        let mut output = Framebuffer::new(width, height);
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, color) in row.into_iter().enumerate() {
+                output.set(x, y, color);
+            }
+        }
+
+        output
+    }
+
+    fn filter_pixel(
+        &self,
+        input: &Framebuffer,
+        features: &Features,
+        x: usize,
+        y: usize,
+        step: usize,
+        sigma_color: f64,
+    ) -> Color {
+        let color_p = input.get(x, y);
+        let normal_p = features.normal.get(x, y);
+        let depth_p = features.depth.get(x, y).x();
+        let albedo_p = features.albedo.get(x, y);
+
+        let mut sum = Color::from(0);
+        let mut weight_sum = 0.0;
+
+        for (j, ky) in KERNEL.iter().enumerate() {
+            for (i, kx) in KERNEL.iter().enumerate() {
+                let qx = x as isize + (i as isize - 2) * step as isize;
+                let qy = y as isize + (j as isize - 2) * step as isize;
+                if qx < 0 || qy < 0 || qx >= input.width() as isize || qy >= input.height() as isize
+                {
+                    continue;
+                }
+                let (qx, qy) = (qx as usize, qy as usize);
+
+                let color_q = input.get(qx, qy);
+                let color_distance = (color_p - color_q).length_squared();
+                let normal_distance = (normal_p - features.normal.get(qx, qy)).length_squared();
+                let depth_distance = (depth_p - features.depth.get(qx, qy).x()) / depth_p.max(1e-4);
+                let albedo_distance = (albedo_p - features.albedo.get(qx, qy)).length_squared();
+
+                let weight = kx
+                    * ky
+                    * (-color_distance / (sigma_color * sigma_color)).exp()
+                    * (-normal_distance / (self.sigma_normal * self.sigma_normal)).exp()
+                    * (-depth_distance * depth_distance / (self.sigma_depth * self.sigma_depth))
+                        .exp()
+                    * (-albedo_distance / (self.sigma_albedo * self.sigma_albedo)).exp();
+
+                sum += color_q * weight;
+                weight_sum += weight;
+            }
+        }
+
+        if weight_sum > 0.0 {
+            sum / weight_sum
+        } else {
+            color_p
+        }
+    }
+}
+
+fn demodulation_albedo(albedo: Color) -> Color {
+    Color::new(
+        albedo.x().max(0.01),
+        albedo.y().max(0.01),
+        albedo.z().max(0.01),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Denoiser, Features};
+    use crate::core::Color;
+    use crate::render::Framebuffer;
+
+    const SIZE: usize = 16;
+
+    fn image(left: Color, right: Color) -> Framebuffer {
+        let mut buffer = Framebuffer::new(SIZE, SIZE);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                buffer.set(x, y, if x < SIZE / 2 { left } else { right });
+            }
+        }
+        buffer
+    }
+
+    fn flat(color: Color) -> Framebuffer {
+        image(color, color)
+    }
+
+    // The largest difference between the two images in any pixel.
+    fn max_difference(a: &Framebuffer, b: &Framebuffer) -> f64 {
+        let mut max: f64 = 0.0;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                max = max.max((a.get(x, y) - b.get(x, y)).length());
+            }
+        }
+        max
+    }
+
+    fn step_is_kept(beauty: &Framebuffer, albedo: &Framebuffer, normal: &Framebuffer) -> bool {
+        let depth = flat(Color::from(1));
+        let features = Features {
+            albedo,
+            normal,
+            depth: &depth,
+        };
+        let denoised = Denoiser::default().denoise(beauty, &features);
+        max_difference(beauty, &denoised) < 1e-3
+    }
+
+    #[test]
+    fn constant_image_is_unchanged() {
+        let beauty = flat(Color::new(0.3, 0.5, 0.7));
+        let albedo = flat(Color::new(0.8, 0.6, 0.4));
+        let normal = flat(Color::new(0, 1, 0));
+        assert!(step_is_kept(&beauty, &albedo, &normal));
+    }
+
+    #[test]
+    fn normal_edges_are_not_blurred_across() {
+        let beauty = image(Color::from(0.2), Color::from(0.6));
+        let albedo = flat(Color::from(1));
+        let normal = image(Color::new(0, 1, 0), Color::new(1, 0, 0));
+        assert!(step_is_kept(&beauty, &albedo, &normal));
+
+        // Without the edge in the normals the same step is blurred.
+        assert!(!step_is_kept(&beauty, &albedo, &flat(Color::new(0, 1, 0))));
+    }
+
+    #[test]
+    fn albedo_edges_are_not_blurred_across() {
+        let beauty = image(Color::from(0.1), Color::from(0.6));
+        let albedo = image(Color::from(0.2), Color::from(0.8));
+        let normal = flat(Color::new(0, 1, 0));
+        assert!(step_is_kept(&beauty, &albedo, &normal));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::core::Color;
+
+use std::io::{self, Write};
+
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::from(0); width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    pub fn write_ppm<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "P3\n{} {}\n255", self.width, self.height)?;
+
+        for row in self.pixels.chunks(self.width) {
+            let line = row.iter().fold(String::new(), |mut acc, pixel| {
+                acc.push_str(&pixel.write_color(1));
+                acc.push(' ');
+                acc
+            });
+            writeln!(out, "{}", line)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_pfm<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "PF\n{} {}\n-1.0\n", self.width, self.height)?;
+
+        for row in self.pixels.chunks(self.width).rev() {
+            for pixel in row {
+                for c in 0..3 {
+                    out.write_all(&(pixel[c] as f32).to_le_bytes())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
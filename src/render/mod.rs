@@ -0,0 +1,165 @@
+mod aov;
+mod denoise;
+mod framebuffer;
+
+use crate::core::{Camera, Color, Ray};
+use crate::environment::Environment;
+use crate::integrators::{Integrator, IntegratorKind};
+use crate::lights::Light;
+use crate::objects::{HitRecord, Object};
+
+pub use aov::{Aov, MaterialIds};
+pub use denoise::{Denoiser, Features};
+pub use framebuffer::Framebuffer;
+
+use rand::prelude::*;
+use rayon::prelude::*;
+
+pub struct Settings {
+    pub image_width: usize,
+    pub image_height: usize,
+    pub samples_per_pixel: usize,
+    pub integrator: IntegratorKind,
+    pub aovs: Vec<Aov>,
+    pub denoiser: Option<Denoiser>,
+}
+
+pub struct Frame {
+    pub beauty: Framebuffer,
+    pub aovs: Vec<(Aov, Framebuffer)>,
+}
+
+pub struct Renderer {
+    settings: Settings,
+    camera: Camera,
+}
+
+impl Renderer {
+    pub fn new(settings: Settings, camera: Camera) -> Self {
+        Self { settings, camera }
+    }
+
+    pub fn render(
+        &self,
+        world: &dyn Object,
+        lights: &[Light],
+        environment: &dyn Environment,
+    ) -> Frame {
+        let integrator = self.settings.integrator.build();
+        self.render_with(world, lights, environment, integrator.as_ref())
+    }
+
+    pub fn render_with(
+        &self,
+        world: &dyn Object,
+        lights: &[Light],
+        environment: &dyn Environment,
+        integrator: &dyn Integrator,
+    ) -> Frame {
+        let Settings {
+            image_width,
+            image_height,
+            samples_per_pixel,
+            aovs: ref requested_aovs,
+            denoiser,
+            ..
+        } = self.settings;
+
+        let mut aovs = requested_aovs.clone();
+        if denoiser.is_some() {
+            for guide in [Aov::Albedo, Aov::Normal, Aov::Depth].iter() {
+                if !aovs.contains(guide) {
+                    aovs.push(*guide);
+                }
+            }
+        }
+
+        let material_ids = MaterialIds::new(world);
+
+        let mut frame = Frame {
+            beauty: Framebuffer::new(image_width, image_height),
+            aovs: aovs
+                .iter()
+                .map(|&aov| (aov, Framebuffer::new(image_width, image_height)))
+                .collect(),
+        };
+
+        for j in (0..image_height).rev() {
+            eprint!("\rScanlines remaining: {:>4}", j);
+
+            let line: Vec<(Color, Vec<Color>)> = (0..image_width)
+                .into_par_iter()
+                .map(|i| {
+                    let mut rng = thread_rng();
+                    let mut pixel_color = Color::from(0);
+                    let mut aov_colors = vec![Color::from(0); aovs.len()];
+
+                    for s in 0..samples_per_pixel {
+                        let u = (i as f64 + rng.gen::<f64>()) / (image_width as f64 - 1.0);
+                        let v = (j as f64 + rng.gen::<f64>()) / (image_height as f64 - 1.0);
+                        let ray = self.camera.ray(u, v, &mut rng);
+
+                        if aovs.is_empty() {
+                            pixel_color +=
+                                integrator.li(&ray, world, lights, environment, &mut rng);
+                            continue;
+                        }
+
+                        // IDs can't be averaged, so they all come from the first sample.
+                        let mut record_first_hit = |ray: &Ray, rec: &HitRecord| {
+                            for (aov, color) in aovs.iter().zip(aov_colors.iter_mut()) {
+                                if !aov.is_id() {
+                                    *color += aov.first_hit(ray, rec, &material_ids);
+                                } else if s == 0 {
+                                    *color = aov.first_hit(ray, rec, &material_ids);
+                                }
+                            }
+                        };
+                        let radiance = integrator.li_split(
+                            &ray,
+                            world,
+                            lights,
+                            environment,
+                            &mut record_first_hit,
+                            &mut rng,
+                        );
+
+                        pixel_color += radiance.total();
+                        for (aov, color) in aovs.iter().zip(aov_colors.iter_mut()) {
+                            *color += aov.lighting(&radiance);
+                        }
+                    }
+
+                    for (aov, color) in aovs.iter().zip(aov_colors.iter_mut()) {
+                        if !aov.is_id() {
+                            *color /= samples_per_pixel as f64;
+                        }
+                    }
+
+                    (pixel_color / samples_per_pixel as f64, aov_colors)
+                })
+                .collect();
+
+            for (i, (color, aov_colors)) in line.into_iter().enumerate() {
+                frame.beauty.set(i, image_height - 1 - j, color);
+                for ((_, buffer), aov_color) in frame.aovs.iter_mut().zip(aov_colors) {
+                    buffer.set(i, image_height - 1 - j, aov_color);
+                }
+            }
+        }
+        eprintln!("\nDone!");
+
+        if let Some(denoiser) = denoiser {
+            let aov_buffer = |aov| &frame.aovs.iter().find(|(a, _)| *a == aov).unwrap().1;
+            let features = Features {
+                albedo: aov_buffer(Aov::Albedo),
+                normal: aov_buffer(Aov::Normal),
+                depth: aov_buffer(Aov::Depth),
+            };
+            frame.beauty = denoiser.denoise(&frame.beauty, &features);
+            frame.aovs.retain(|(aov, _)| requested_aovs.contains(aov));
+        }
+
+        frame
+    }
+}
@@ -1,4 +1,6 @@
-use crate::core::{Point, Vector};
+use super::Scene;
+use crate::core::{Color, Point, Vector};
+use crate::materials::DiffuseLight;
 use crate::materials::Material::*;
 use crate::objects::*;
 use crate::textures::SolidColor;
@@ -6,7 +8,7 @@ use crate::textures::SolidColor;
 use rand::prelude::*;
 
 #[allow(dead_code)]
-pub fn scene(_rng: &mut ThreadRng) -> ObjectList {
+pub fn scene(_rng: &mut ThreadRng) -> Scene {
     let mut world = ObjectList::new();
 
     let red = Lambertian {
@@ -18,17 +20,15 @@ pub fn scene(_rng: &mut ThreadRng) -> ObjectList {
     let green = Lambertian {
         albedo: SolidColor::new_texture(0.12, 0.45, 0.15),
     };
-    let light = DiffuseLight {
-        emit: SolidColor::from(15),
-    };
+    let light = DiffuseLight::new_one_sided_material(SolidColor::from(15));
 
     world.add(Box::new(FlipFace::new(YZRect::new(
         0.0, 555.0, 0.0, 555.0, 555.0, green,
     ))));
     world.add(Box::new(YZRect::new(0.0, 555.0, 0.0, 555.0, 0.0, red)));
-    world.add(Box::new(XZRect::new(
+    world.add(Box::new(FlipFace::new(XZRect::new(
         213.0, 343.0, 227.0, 332.0, 554.0, light,
-    )));
+    ))));
     world.add(Box::new(FlipFace::new(XZRect::new(
         0.0,
         555.0,
@@ -64,5 +64,9 @@ pub fn scene(_rng: &mut ThreadRng) -> ObjectList {
     let box2 = Box::new(Translate::new(box2, Vector::new(130, 0, 65)));
     world.add(box2);
 
-    world
+    Scene {
+        world,
+        environment: Box::new(Color::from(0)),
+        lights: Vec::new(),
+    }
 }
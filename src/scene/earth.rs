@@ -1,4 +1,6 @@
+use super::Scene;
 use crate::core::Point;
+use crate::environment::Gradient;
 use crate::materials::Material::*;
 use crate::objects::*;
 use crate::textures::Image;
@@ -6,7 +8,7 @@ use crate::textures::Image;
 use rand::prelude::*;
 
 #[allow(dead_code)]
-pub fn scene(_rng: &mut ThreadRng) -> ObjectList {
+pub fn scene(_rng: &mut ThreadRng) -> Scene {
     let mut world = ObjectList::new();
     let earth_texture = Image::new_image("earthmap.jpg").unwrap();
     let earth_surace = Lambertian {
@@ -16,5 +18,9 @@ pub fn scene(_rng: &mut ThreadRng) -> ObjectList {
 
     world.add(globe);
 
-    world
+    Scene {
+        world,
+        environment: Box::new(Gradient::sky()),
+        lights: Vec::new(),
+    }
 }
@@ -1,12 +1,14 @@
+use super::Scene;
 use crate::core::{Color, Point, Vector};
 use crate::materials::Material::*;
+use crate::materials::{Dielectric, DiffuseLight};
 use crate::objects::*;
 use crate::textures::{Image, Noise, SolidColor};
 
 use rand::prelude::*;
 
 #[allow(dead_code)]
-pub fn scene(rng: &mut ThreadRng) -> ObjectList {
+pub fn scene(rng: &mut ThreadRng) -> Scene {
     let mut boxes = ObjectList::new();
 
     let ground = Lambertian {
@@ -37,12 +39,10 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
 
     world.add(Box::new(Bvh::new(boxes.objects, 0.0..1.0, rng)));
 
-    let light = DiffuseLight {
-        emit: SolidColor::from(7.0),
-    };
-    world.add(Box::new(XZRect::new(
+    let light = DiffuseLight::new_one_sided_material(SolidColor::from(7.0));
+    world.add(Box::new(FlipFace::new(XZRect::new(
         123.0, 423.0, 147.0, 412.0, 554.0, light,
-    )));
+    ))));
 
     let center1 = Point::new(400.0, 400.0, 200.0);
     let center2 = center1 + Vector::new(30.0, 0.0, 0.0);
@@ -61,7 +61,7 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
     world.add(Box::new(Sphere::new(
         Point::new(260, 150, 45),
         50.0,
-        Dielectric { ref_index: 1.5 },
+        Dielectric::new_material(1.5),
     )));
 
     world.add(Box::new(Sphere::new(
@@ -69,14 +69,14 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
         50.0,
         Metal {
             albedo: Color::new(0.8, 0.8, 0.9),
-            fuzz: 10.0,
+            fuzz: 1.0,
         },
     )));
 
     let boundary = Sphere::new(
         Point::new(360, 150, 145),
         70.0,
-        Dielectric { ref_index: 1.5 },
+        Dielectric::new_material(1.5),
     );
     world.add(Box::new(boundary.clone()));
     world.add(Box::new(ConstantMedium::new(
@@ -85,7 +85,7 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
         SolidColor::new_texture(0.2, 0.4, 0.9),
     )));
 
-    let boundary = Sphere::new(Point::from(0), 5000.0, Dielectric { ref_index: 1.5 });
+    let boundary = Sphere::new(Point::from(0), 5000.0, Dielectric::new_material(1.5));
     world.add(Box::new(ConstantMedium::new(
         boundary,
         0.0001,
@@ -128,5 +128,9 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
         Vector::new(-100, 270, 395),
     )));
 
-    world
+    Scene {
+        world,
+        environment: Box::new(Color::from(0)),
+        lights: Vec::new(),
+    }
 }
@@ -1,15 +1,27 @@
+use crate::environment::Environment;
+use crate::lights::Light;
 use crate::objects::ObjectList;
 
 mod cornell_box;
 mod cornell_smoke;
 mod earth;
 mod final_scene;
+mod punctual_lights;
 mod random_scene;
 mod simple_light;
 mod two_spheres;
 
 use rand::prelude::*;
 
-pub fn scene(rng: &mut ThreadRng) -> ObjectList {
+// Everything a render needs besides the camera.
+pub struct Scene {
+    pub world: ObjectList,
+    // Radiance arriving along rays that leave `world`.
+    pub environment: Box<dyn Environment>,
+    // Point-like lights, which are sampled at every shading point.
+    pub lights: Vec<Light>,
+}
+
+pub fn scene(rng: &mut ThreadRng) -> Scene {
     final_scene::scene(rng)
 }
@@ -0,0 +1,70 @@
+use super::Scene;
+use crate::core::{Color, Point, Vector};
+use crate::lights::{Light, Spot};
+use crate::materials::Material::*;
+use crate::materials::{Conductor, Dielectric};
+use crate::objects::*;
+use crate::textures::{Checker, SolidColor};
+
+use rand::prelude::*;
+
+// Three spheres lit only by a low directional sun, a warm point light and a
+// pair of spots, one with a photometric profile that brightens off its axis.
+#[allow(dead_code)]
+pub fn scene(_rng: &mut ThreadRng) -> Scene {
+    let mut world = ObjectList::new();
+
+    let checker = Checker::new_texture(
+        Box::new(SolidColor::new_texture(0.2, 0.3, 0.1)),
+        Box::new(SolidColor::new_texture(0.9, 0.9, 0.9)),
+    );
+    world.add(Box::new(Sphere::new(
+        Point::new(0, -1000, 0),
+        1000.0,
+        Lambertian { albedo: checker },
+    )));
+
+    world.add(Box::new(Sphere::new(
+        Point::new(-4, 1, 0),
+        1.0,
+        Lambertian {
+            albedo: SolidColor::new_texture(0.4, 0.2, 0.1),
+        },
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(0, 1, 0),
+        1.0,
+        Dielectric::new_material(1.5),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(4, 1, 0),
+        1.0,
+        Conductor::gold(0.3),
+    )));
+
+    let lights = vec![
+        Light::directional(Vector::new(-1, -0.4, -0.6), Color::new(1.0, 0.9, 0.7)),
+        Light::point(Point::new(0, 4, 3), Color::new(12.0, 8.0, 4.0)),
+        Spot::new_light(
+            Point::new(-4, 5, 2),
+            Point::new(-4, 0, 0),
+            Color::from(40.0),
+            25.0,
+            15.0,
+        ),
+        Spot::new_profiled_light(
+            Point::new(4, 5, 2),
+            Point::new(4, 0, 0),
+            Color::from(40.0),
+            35.0,
+            30.0,
+            vec![0.4, 0.7, 1.0, 0.8, 0.0],
+        ),
+    ];
+
+    Scene {
+        world,
+        environment: Box::new(Color::new(0.01, 0.01, 0.02)),
+        lights,
+    }
+}
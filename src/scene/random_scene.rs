@@ -1,4 +1,7 @@
+use super::Scene;
 use crate::core::{Color, Point, Vector};
+use crate::environment::Gradient;
+use crate::materials::Dielectric;
 use crate::materials::Material::{self, *};
 use crate::objects::*;
 use crate::textures::{Checker, SolidColor};
@@ -6,7 +9,7 @@ use crate::textures::{Checker, SolidColor};
 use rand::prelude::*;
 
 #[allow(dead_code)]
-pub fn scene(rng: &mut ThreadRng) -> ObjectList {
+pub fn scene(rng: &mut ThreadRng) -> Scene {
     let mut world = ObjectList::new();
 
     let checker = Checker::new_texture(
@@ -51,14 +54,14 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
                     sphere_material = Metal { albedo, fuzz };
                     world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
                 } else {
-                    sphere_material = Dielectric { ref_index: 1.5 };
+                    sphere_material = Dielectric::new_material(1.5);
                     world.add(Box::new(Sphere::new(center, 0.2, sphere_material)));
                 }
             }
         }
     }
 
-    let material1 = Dielectric { ref_index: 1.5 };
+    let material1 = Dielectric::new_material(1.5);
     world.add(Box::new(Sphere::new(Point::new(0, 1, 0), 1.0, material1)));
 
     let material2 = Lambertian {
@@ -72,5 +75,9 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
     };
     world.add(Box::new(Sphere::new(Point::new(4, 1, 0), 1.0, material3)));
 
-    world
+    Scene {
+        world,
+        environment: Box::new(Gradient::sky()),
+        lights: Vec::new(),
+    }
 }
@@ -1,4 +1,6 @@
-use crate::core::Point;
+use super::Scene;
+use crate::core::{Color, Point};
+use crate::materials::DiffuseLight;
 use crate::materials::Material::*;
 use crate::objects::*;
 use crate::textures::{Noise, SolidColor};
@@ -6,7 +8,7 @@ use crate::textures::{Noise, SolidColor};
 use rand::prelude::*;
 
 #[allow(dead_code)]
-pub fn scene(rng: &mut ThreadRng) -> ObjectList {
+pub fn scene(rng: &mut ThreadRng) -> Scene {
     let mut world = ObjectList::new();
 
     let pertext = Noise::new_texture(4.0, rng);
@@ -23,9 +25,7 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
         Lambertian { albedo: pertext },
     )));
 
-    let diffuse_light = DiffuseLight {
-        emit: SolidColor::from(4),
-    };
+    let diffuse_light = DiffuseLight::new_material(SolidColor::from(4));
     world.add(Box::new(Sphere::new(
         Point::new(0, 7, 0),
         2.0,
@@ -40,5 +40,9 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
         diffuse_light,
     )));
 
-    world
+    Scene {
+        world,
+        environment: Box::new(Color::from(0)),
+        lights: Vec::new(),
+    }
 }
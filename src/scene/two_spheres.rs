@@ -1,4 +1,6 @@
+use super::Scene;
 use crate::core::Point;
+use crate::environment::Gradient;
 use crate::materials::Material::*;
 use crate::objects::*;
 use crate::textures::Noise;
@@ -6,7 +8,7 @@ use crate::textures::Noise;
 use rand::prelude::*;
 
 #[allow(dead_code)]
-pub fn scene(rng: &mut ThreadRng) -> ObjectList {
+pub fn scene(rng: &mut ThreadRng) -> Scene {
     let mut world = ObjectList::new();
 
     let pertext = Noise::new_texture(5.0, rng);
@@ -25,5 +27,9 @@ pub fn scene(rng: &mut ThreadRng) -> ObjectList {
         Lambertian { albedo: pertext },
     )));
 
-    world
+    Scene {
+        world,
+        environment: Box::new(Gradient::sky()),
+        lights: Vec::new(),
+    }
 }
@@ -0,0 +1,128 @@
+mod smits;
+mod spd;
+mod wavelengths;
+
+use crate::core::{Color, Vector};
+
+pub use smits::rgb_to_spectrum;
+pub use spd::Spectrum;
+pub use wavelengths::Wavelengths;
+
+use rand::prelude::*;
+
+pub const LAMBDA_MIN: f64 = 380.0;
+pub const LAMBDA_MAX: f64 = 780.0;
+
+// sRGB of the equal energy spectrum, used to keep a flat spectrum of 1 white.
+const WHITE_RGB: [f64; 3] = [128.361, 101.538, 97.065];
+
+// Integrals of the positive part of each sRGB channel of the colour matching functions.
+const RGB_INTEGRAL: [f64; 3] = [176.177, 115.386, 109.318];
+
+// Multi-lobe fit of the CIE 1931 colour matching functions (Wyman et al. 2013).
+pub fn cie_xyz(lambda: f64) -> Vector {
+    let g = |mu: f64, sigma1: f64, sigma2: f64| {
+        let sigma = if lambda < mu { sigma1 } else { sigma2 };
+        let t = (lambda - mu) / sigma;
+        (-0.5 * t * t).exp()
+    };
+
+    Vector::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+pub fn xyz_to_rgb(xyz: Vector) -> Color {
+    Color::new(
+        3.240_454_2 * xyz.x() - 1.537_138_5 * xyz.y() - 0.498_531_4 * xyz.z(),
+        -0.969_266_0 * xyz.x() + 1.876_010_8 * xyz.y() + 0.041_556_0 * xyz.z(),
+        0.055_643_4 * xyz.x() - 0.204_025_9 * xyz.y() + 1.057_225_2 * xyz.z(),
+    )
+}
+
+// Samples a wavelength uniformly over the visible range, returning it together with the RGB
+// weight that makes a path carrying only that wavelength an unbiased estimate of white light.
+pub fn sample_wavelength(rng: &mut ThreadRng) -> (f64, Color) {
+    let lambda = rng.gen_range(LAMBDA_MIN, LAMBDA_MAX);
+    let rgb = xyz_to_rgb(cie_xyz(lambda));
+    let channel = |c: usize| rgb[c].max(0.0) * (LAMBDA_MAX - LAMBDA_MIN) / RGB_INTEGRAL[c];
+
+    (lambda, Color::new(channel(0), channel(1), channel(2)))
+}
+
+// White balanced RGB of a spectral function, integrated with `steps` midpoint samples.
+pub fn integrate_rgb<F: Fn(f64) -> f64>(f: F, steps: usize) -> Color {
+    let step = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+
+    let mut rgb = Color::from(0);
+    for i in 0..steps {
+        let lambda = LAMBDA_MIN + (i as f64 + 0.5) * step;
+        rgb += xyz_to_rgb(cie_xyz(lambda)) * f(lambda) * step;
+    }
+
+    white_balance(rgb)
+}
+
+// Converts an unnormalised integral of `xyz_to_rgb(cie_xyz(λ)) * s(λ)` so that s(λ) = 1 is white.
+pub fn white_balance(rgb: Color) -> Color {
+    Color::new(
+        rgb.x() / WHITE_RGB[0],
+        rgb.y() / WHITE_RGB[1],
+        rgb.z() / WHITE_RGB[2],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_white(rgb: Color, tolerance: f64) {
+        let white = Color::from(1);
+        assert!((rgb - white).length() < tolerance, "{:?} isn't white", rgb);
+    }
+
+    #[test]
+    fn sampled_wavelength_weights_average_to_white() {
+        let mut rng = rand::thread_rng();
+        let samples = 200_000;
+
+        let mut total = Color::from(0);
+        for _ in 0..samples {
+            total += sample_wavelength(&mut rng).1;
+        }
+        assert_white(total / samples as f64, 0.01);
+    }
+
+    #[test]
+    fn flat_spectra_integrate_to_white() {
+        assert_white(integrate_rgb(|_| 1.0, 400), 0.01);
+        assert_white(
+            Spectrum::Sampled(vec![(300.0, 1.0), (800.0, 1.0)]).to_rgb(),
+            0.01,
+        );
+    }
+
+    #[test]
+    fn white_round_trips_through_sampled_wavelengths() {
+        let mut rng = rand::thread_rng();
+        let samples = 50_000;
+
+        let mut total = Color::from(0);
+        for _ in 0..samples {
+            let wavelengths = Wavelengths::sample(&mut rng);
+            total += wavelengths.to_rgb(wavelengths.uplift(Color::from(1)));
+        }
+        assert_white(total / samples as f64, 0.01);
+    }
+
+    #[test]
+    fn rgb_to_spectrum_keeps_grey_flat() {
+        for i in 0..=40 {
+            let lambda = LAMBDA_MIN + i as f64 * 10.0;
+            let value = rgb_to_spectrum(Color::from(0.5), lambda);
+            assert!((value - 0.5).abs() < 1e-3, "{} at {}nm", value, lambda);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::LAMBDA_MIN;
+use crate::core::Color;
+
+const BINS: usize = 10;
+const BIN_MAX: f64 = 720.0;
+
+// Basis spectra from Smits 1999, sampled at ten evenly spaced wavelengths over 380-720nm.
+const WHITE: [f64; BINS] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const CYAN: [f64; BINS] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const MAGENTA: [f64; BINS] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const YELLOW: [f64; BINS] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const RED: [f64; BINS] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const GREEN: [f64; BINS] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const BLUE: [f64; BINS] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+fn lookup(basis: &[f64; BINS], lambda: f64) -> f64 {
+    let x = (lambda - LAMBDA_MIN) / (BIN_MAX - LAMBDA_MIN) * (BINS - 1) as f64;
+    if x <= 0.0 {
+        return basis[0];
+    }
+    if x >= (BINS - 1) as f64 {
+        return basis[BINS - 1];
+    }
+
+    let i = x as usize;
+    let t = x - i as f64;
+    basis[i] * (1.0 - t) + basis[i + 1] * t
+}
+
+pub fn rgb_to_spectrum(rgb: Color, lambda: f64) -> f64 {
+    let (r, g, b) = (rgb.x(), rgb.y(), rgb.z());
+    let at = |basis| lookup(basis, lambda);
+
+    if r <= g && r <= b {
+        let white = r * at(&WHITE);
+        if g <= b {
+            white + (g - r) * at(&CYAN) + (b - g) * at(&BLUE)
+        } else {
+            white + (b - r) * at(&CYAN) + (g - b) * at(&GREEN)
+        }
+    } else if g <= r && g <= b {
+        let white = g * at(&WHITE);
+        if r <= b {
+            white + (r - g) * at(&MAGENTA) + (b - r) * at(&BLUE)
+        } else {
+            white + (b - g) * at(&MAGENTA) + (r - b) * at(&RED)
+        }
+    } else {
+        let white = b * at(&WHITE);
+        if r <= g {
+            white + (r - b) * at(&YELLOW) + (g - r) * at(&GREEN)
+        } else {
+            white + (g - b) * at(&YELLOW) + (r - g) * at(&RED)
+        }
+    }
+}
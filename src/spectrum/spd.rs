@@ -0,0 +1,48 @@
+use super::integrate_rgb;
+use crate::core::Color;
+
+// Spectral power distributions for light sources, normalised so their peak is 1.
+#[derive(Clone, Debug)]
+pub enum Spectrum {
+    Blackbody { temperature: f64 },
+    // Measured samples as (wavelength in nm, relative power), sorted by wavelength.
+    Sampled(Vec<(f64, f64)>),
+}
+
+impl Spectrum {
+    pub fn value(&self, lambda: f64) -> f64 {
+        match self {
+            Spectrum::Blackbody { temperature } => {
+                let peak = 2.897_771_955e6 / temperature;
+                planck(lambda, *temperature) / planck(peak, *temperature)
+            }
+            Spectrum::Sampled(samples) => {
+                let i = samples.partition_point(|&(l, _)| l < lambda);
+                if i == 0 {
+                    samples.first().map_or(0.0, |&(_, v)| v)
+                } else if i == samples.len() {
+                    samples.last().map_or(0.0, |&(_, v)| v)
+                } else {
+                    let (l0, v0) = samples[i - 1];
+                    let (l1, v1) = samples[i];
+                    v0 + (v1 - v0) * (lambda - l0) / (l1 - l0)
+                }
+            }
+        }
+    }
+
+    // Linear sRGB colour of the spectrum, matching what the spectral integrator produces.
+    pub fn to_rgb(&self) -> Color {
+        integrate_rgb(|lambda| self.value(lambda), 400)
+    }
+}
+
+// Planck's law for a wavelength in nm, up to a constant factor.
+fn planck(lambda: f64, temperature: f64) -> f64 {
+    let c = 299_792_458.0;
+    let h = 6.626_070_15e-34;
+    let kb = 1.380_649e-23;
+
+    let l = lambda * 1e-9;
+    (2.0 * h * c * c) / (l.powi(5) * ((h * c / (l * kb * temperature)).exp() - 1.0))
+}
@@ -0,0 +1,59 @@
+use super::{cie_xyz, rgb_to_spectrum, white_balance, xyz_to_rgb, LAMBDA_MAX, LAMBDA_MIN};
+use crate::core::Color;
+
+use rand::prelude::*;
+
+const COUNT: usize = 3;
+
+// Three stratified wavelengths sampled per path, the first being the hero wavelength. Spectral
+// quantities along the path are stored as a `Color` holding one value per wavelength.
+#[derive(Copy, Clone, Debug)]
+pub struct Wavelengths {
+    lambda: [f64; COUNT],
+    secondary_terminated: bool,
+}
+
+impl Wavelengths {
+    pub fn sample(rng: &mut ThreadRng) -> Self {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let hero = rng.gen_range(LAMBDA_MIN, LAMBDA_MAX);
+
+        let mut lambda = [hero; COUNT];
+        for (i, l) in lambda.iter_mut().enumerate().skip(1) {
+            *l = LAMBDA_MIN + (hero - LAMBDA_MIN + i as f64 * range / COUNT as f64) % range;
+        }
+
+        Self {
+            lambda,
+            secondary_terminated: false,
+        }
+    }
+
+    pub fn hero(&self) -> f64 {
+        self.lambda[0]
+    }
+
+    // Wavelength-dependent scattering such as dispersion can only follow the hero wavelength.
+    pub fn terminate_secondary(&mut self) {
+        self.secondary_terminated = true;
+    }
+
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Color {
+        Color::new(f(self.lambda[0]), f(self.lambda[1]), f(self.lambda[2]))
+    }
+
+    pub fn uplift(&self, rgb: Color) -> Color {
+        self.map(|lambda| rgb_to_spectrum(rgb, lambda))
+    }
+
+    pub fn to_rgb(&self, values: Color) -> Color {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let active = if self.secondary_terminated { 1 } else { COUNT };
+
+        let mut rgb = Color::from(0);
+        for i in 0..active {
+            rgb += xyz_to_rgb(cie_xyz(self.lambda[i])) * values[i] * range;
+        }
+        white_balance(rgb / active as f64)
+    }
+}
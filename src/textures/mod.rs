@@ -28,4 +28,10 @@ impl Texture {
             Texture::SolidColor(t) => t.value(u, v, p),
         }
     }
+
+    // Single channel reading for masks and roughness maps; the mean of the channels.
+    pub fn scalar(&self, u: f64, v: f64, p: Point) -> f64 {
+        let color = self.value(u, v, p);
+        (color.x() + color.y() + color.z()) / 3.0
+    }
 }
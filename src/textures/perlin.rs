@@ -32,6 +32,7 @@ impl Perlin {
         }
     }
 
+    #[allow(clippy::needless_range_loop)]
     pub fn noise(&self, p: Point) -> f64 {
         let u = p.x() - p.x().floor();
         let v = p.y() - p.y().floor();